@@ -0,0 +1,533 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
+};
+use std::str::FromStr;
+
+// Limbs are stored little-endian in base 10^9 so that decimal conversion is
+// linear.
+const BASE: u64 = 1_000_000_000;
+const BASE_DIGITS: usize = 9;
+const KARATSUBA_THRESHOLD: usize = 32;
+
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigInt {
+    negative: bool,
+    limbs: Vec<u32>,
+}
+
+impl BigInt {
+    pub fn zero() -> Self {
+        BigInt::default()
+    }
+
+    pub fn one() -> Self {
+        BigInt::from(1u64)
+    }
+
+    fn from_parts(negative: bool, mut limbs: Vec<u32>) -> Self {
+        trim(&mut limbs);
+        let negative = negative && !limbs.is_empty();
+        BigInt { negative, limbs }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn signum(&self) -> i32 {
+        if self.is_zero() {
+            0
+        } else if self.negative {
+            -1
+        } else {
+            1
+        }
+    }
+
+    pub fn abs(&self) -> Self {
+        BigInt {
+            negative: false,
+            limbs: self.limbs.clone(),
+        }
+    }
+
+    pub fn pow(&self, mut exp: u32) -> Self {
+        let mut base = self.clone();
+        let mut result = BigInt::one();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+
+    // Quotient truncated toward zero and remainder with the sign of self,
+    // matching the primitive integer operators.
+    pub fn div_rem(&self, rhs: &BigInt) -> (BigInt, BigInt) {
+        assert!(!rhs.is_zero(), "Division by zero");
+        let (q, r) = div_rem_mag(&self.limbs, &rhs.limbs);
+        (
+            BigInt::from_parts(self.negative != rhs.negative, q),
+            BigInt::from_parts(self.negative, r),
+        )
+    }
+
+    pub fn to_i64(&self) -> Option<i64> {
+        if self.limbs.len() > 3 {
+            return None;
+        }
+        let magnitude = self
+            .limbs
+            .iter()
+            .rev()
+            .fold(0i128, |acc, &limb| acc * BASE as i128 + limb as i128);
+        let value = if self.negative { -magnitude } else { magnitude };
+        i64::try_from(value).ok()
+    }
+}
+
+fn trim(limbs: &mut Vec<u32>) {
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+}
+
+fn cmp_mag(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut result = Vec::with_capacity(a.len() + 1);
+    let mut carry = 0;
+    for (i, &x) in a.iter().enumerate() {
+        let sum = x as u64 + b.get(i).copied().unwrap_or(0) as u64 + carry;
+        result.push((sum % BASE) as u32);
+        carry = sum / BASE;
+    }
+    if carry > 0 {
+        result.push(carry as u32);
+    }
+    result
+}
+
+// Requires |a| >= |b|.
+fn sub_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0;
+    for (i, &x) in a.iter().enumerate() {
+        let mut diff = x as i64 - b.get(i).copied().unwrap_or(0) as i64 - borrow;
+        borrow = 0;
+        if diff < 0 {
+            diff += BASE as i64;
+            borrow = 1;
+        }
+        result.push(diff as u32);
+    }
+    trim(&mut result);
+    result
+}
+
+fn mul_naive(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut result = vec![0u64; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0;
+        for (j, &y) in b.iter().enumerate() {
+            let cur = result[i + j] + x as u64 * y as u64 + carry;
+            result[i + j] = cur % BASE;
+            carry = cur / BASE;
+        }
+        result[i + b.len()] += carry;
+    }
+    let mut result: Vec<u32> = result.into_iter().map(|x| x as u32).collect();
+    trim(&mut result);
+    result
+}
+
+fn mul_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.len().min(b.len()) < KARATSUBA_THRESHOLD {
+        return mul_naive(a, b);
+    }
+
+    let half = a.len().max(b.len()) / 2;
+    let split = |x: &[u32]| {
+        let (low, high) = x.split_at(half.min(x.len()));
+        let mut low = low.to_vec();
+        trim(&mut low);
+        (low, high.to_vec())
+    };
+    let (a0, a1) = split(a);
+    let (b0, b1) = split(b);
+
+    let z0 = mul_mag(&a0, &b0);
+    let z2 = mul_mag(&a1, &b1);
+    let z1 = mul_mag(&add_mag(&a0, &a1), &add_mag(&b0, &b1));
+    let z1 = sub_mag(&sub_mag(&z1, &z0), &z2);
+
+    let mut result = vec![0u32; a.len() + b.len() + 1];
+    for (shift, part) in [(0, &z0), (half, &z1), (2 * half, &z2)] {
+        let mut carry = 0;
+        let mut i = 0;
+        while i < part.len() || carry > 0 {
+            let limb = part.get(i).copied().unwrap_or(0);
+            let sum = result[shift + i] as u64 + limb as u64 + carry;
+            result[shift + i] = (sum % BASE) as u32;
+            carry = sum / BASE;
+            i += 1;
+        }
+    }
+    trim(&mut result);
+    result
+}
+
+fn div_rem_small(a: &[u32], d: u32) -> (Vec<u32>, u32) {
+    let mut quotient = vec![0u32; a.len()];
+    let mut rem = 0u64;
+    for i in (0..a.len()).rev() {
+        let cur = rem * BASE + a[i] as u64;
+        quotient[i] = (cur / d as u64) as u32;
+        rem = cur % d as u64;
+    }
+    trim(&mut quotient);
+    (quotient, rem as u32)
+}
+
+fn mul_small(a: &[u32], m: u32) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len() + 1);
+    let mut carry = 0;
+    for &x in a {
+        let cur = x as u64 * m as u64 + carry;
+        result.push((cur % BASE) as u32);
+        carry = cur / BASE;
+    }
+    result.push(carry as u32);
+    result
+}
+
+// Knuth's algorithm D in base 10^9.
+fn div_rem_mag(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if cmp_mag(a, b) == Ordering::Less {
+        return (Vec::new(), a.to_vec());
+    }
+    if b.len() == 1 {
+        let (q, r) = div_rem_small(a, b[0]);
+        let mut r = vec![r];
+        trim(&mut r);
+        return (q, r);
+    }
+
+    // Scale so that the leading limb of the divisor is at least BASE / 2.
+    let scale = (BASE / (*b.last().unwrap() as u64 + 1)) as u32;
+    let mut u: Vec<u64> = mul_small(a, scale).into_iter().map(|x| x as u64).collect();
+    let v: Vec<u64> = mul_small(b, scale)[..b.len()]
+        .iter()
+        .map(|&x| x as u64)
+        .collect();
+    let n = v.len();
+    let m = a.len() - n;
+    let mut quotient = vec![0u32; m + 1];
+
+    for j in (0..=m).rev() {
+        let top = u[j + n] * BASE + u[j + n - 1];
+        let mut qhat = top / v[n - 1];
+        let mut rhat = top % v[n - 1];
+        while qhat >= BASE || qhat * v[n - 2] > rhat * BASE + u[j + n - 2] {
+            qhat -= 1;
+            rhat += v[n - 1];
+            if rhat >= BASE {
+                break;
+            }
+        }
+
+        let mut carry = 0;
+        let mut borrow = 0;
+        for i in 0..n {
+            let product = qhat * v[i] + carry;
+            carry = product / BASE;
+            let mut diff = u[i + j] as i64 - (product % BASE) as i64 - borrow;
+            borrow = 0;
+            if diff < 0 {
+                diff += BASE as i64;
+                borrow = 1;
+            }
+            u[i + j] = diff as u64;
+        }
+        let diff = u[j + n] as i64 - carry as i64 - borrow;
+
+        if diff < 0 {
+            qhat -= 1;
+            let mut carry = 0;
+            for i in 0..n {
+                let sum = u[i + j] + v[i] + carry;
+                u[i + j] = sum % BASE;
+                carry = sum / BASE;
+            }
+            u[j + n] = (diff + carry as i64) as u64;
+        } else {
+            u[j + n] = diff as u64;
+        }
+        quotient[j] = qhat as u32;
+    }
+
+    trim(&mut quotient);
+    let mut remainder: Vec<u32> = u[..n].iter().map(|&x| x as u32).collect();
+    trim(&mut remainder);
+    let (remainder, _) = div_rem_small(&remainder, scale);
+    (quotient, remainder)
+}
+
+macro_rules! impl_bigint_from_unsigned {
+    ($($t:ty),*) => {$(
+        impl From<$t> for BigInt {
+            fn from(value: $t) -> Self {
+                let mut value = value as u128;
+                let mut limbs = Vec::new();
+                while value > 0 {
+                    limbs.push((value % BASE as u128) as u32);
+                    value /= BASE as u128;
+                }
+                BigInt { negative: false, limbs }
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_bigint_from_signed {
+    ($($t:ty),*) => {$(
+        impl From<$t> for BigInt {
+            fn from(value: $t) -> Self {
+                let magnitude = BigInt::from(value.unsigned_abs());
+                BigInt::from_parts(value < 0, magnitude.limbs)
+            }
+        }
+    )*};
+}
+
+impl_bigint_from_unsigned!(u32, u64, u128, usize);
+impl_bigint_from_signed!(i32, i64, i128, isize);
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_mag(&self.limbs, &other.limbs),
+            (true, true) => cmp_mag(&other.limbs, &self.limbs),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.limbs)
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        -self.clone()
+    }
+}
+
+impl Add<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, rhs: &BigInt) -> BigInt {
+        if self.negative == rhs.negative {
+            return BigInt::from_parts(self.negative, add_mag(&self.limbs, &rhs.limbs));
+        }
+        match cmp_mag(&self.limbs, &rhs.limbs) {
+            Ordering::Less => BigInt::from_parts(rhs.negative, sub_mag(&rhs.limbs, &self.limbs)),
+            _ => BigInt::from_parts(self.negative, sub_mag(&self.limbs, &rhs.limbs)),
+        }
+    }
+}
+
+impl Sub<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, rhs: &BigInt) -> BigInt {
+        self + &-rhs
+    }
+}
+
+impl Mul<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, rhs: &BigInt) -> BigInt {
+        BigInt::from_parts(
+            self.negative != rhs.negative,
+            mul_mag(&self.limbs, &rhs.limbs),
+        )
+    }
+}
+
+impl Div<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn div(self, rhs: &BigInt) -> BigInt {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn rem(self, rhs: &BigInt) -> BigInt {
+        self.div_rem(rhs).1
+    }
+}
+
+macro_rules! forward_bigint_binop {
+    ($op:ident, $method:ident, $op_assign:ident, $method_assign:ident) => {
+        impl $op<BigInt> for BigInt {
+            type Output = BigInt;
+
+            fn $method(self, rhs: BigInt) -> BigInt {
+                $op::$method(&self, &rhs)
+            }
+        }
+
+        impl $op<&BigInt> for BigInt {
+            type Output = BigInt;
+
+            fn $method(self, rhs: &BigInt) -> BigInt {
+                $op::$method(&self, rhs)
+            }
+        }
+
+        impl $op<BigInt> for &BigInt {
+            type Output = BigInt;
+
+            fn $method(self, rhs: BigInt) -> BigInt {
+                $op::$method(self, &rhs)
+            }
+        }
+
+        impl $op_assign<BigInt> for BigInt {
+            fn $method_assign(&mut self, rhs: BigInt) {
+                *self = $op::$method(&*self, &rhs);
+            }
+        }
+
+        impl $op_assign<&BigInt> for BigInt {
+            fn $method_assign(&mut self, rhs: &BigInt) {
+                *self = $op::$method(&*self, rhs);
+            }
+        }
+    };
+}
+
+forward_bigint_binop!(Add, add, AddAssign, add_assign);
+forward_bigint_binop!(Sub, sub, SubAssign, sub_assign);
+forward_bigint_binop!(Mul, mul, MulAssign, mul_assign);
+forward_bigint_binop!(Div, div, DivAssign, div_assign);
+forward_bigint_binop!(Rem, rem, RemAssign, rem_assign);
+
+impl Sum for BigInt {
+    fn sum<It: Iterator<Item = Self>>(iter: It) -> Self {
+        iter.fold(BigInt::zero(), |acc, x| acc + x)
+    }
+}
+
+impl<'a> Sum<&'a BigInt> for BigInt {
+    fn sum<It: Iterator<Item = &'a Self>>(iter: It) -> Self {
+        iter.fold(BigInt::zero(), |acc, x| acc + x)
+    }
+}
+
+impl Product for BigInt {
+    fn product<It: Iterator<Item = Self>>(iter: It) -> Self {
+        iter.fold(BigInt::one(), |acc, x| acc * x)
+    }
+}
+
+impl<'a> Product<&'a BigInt> for BigInt {
+    fn product<It: Iterator<Item = &'a Self>>(iter: It) -> Self {
+        iter.fold(BigInt::one(), |acc, x| acc * x)
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut digits = match self.limbs.last() {
+            Some(top) => top.to_string(),
+            None => return f.pad_integral(true, "", "0"),
+        };
+        for limb in self.limbs.iter().rev().skip(1) {
+            digits.push_str(&format!("{:09}", limb));
+        }
+        f.pad_integral(!self.negative, "", &digits)
+    }
+}
+
+impl fmt::Debug for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        if digits.is_empty() || !digits.bytes().all(|c| c.is_ascii_digit()) {
+            return Err(ParseBigIntError);
+        }
+
+        let bytes = digits.as_bytes();
+        let mut limbs = Vec::with_capacity(bytes.len() / BASE_DIGITS + 1);
+        let mut end = bytes.len();
+        while end > 0 {
+            let start = end.saturating_sub(BASE_DIGITS);
+            let limb = bytes[start..end]
+                .iter()
+                .fold(0u32, |acc, &c| acc * 10 + (c - b'0') as u32);
+            limbs.push(limb);
+            end = start;
+        }
+        Ok(BigInt::from_parts(negative, limbs))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBigIntError;
+
+impl fmt::Display for ParseBigIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid digit found in string")
+    }
+}
+
+impl std::error::Error for ParseBigIntError {}
@@ -0,0 +1,311 @@
+use super::convolution::convolution_any_mod;
+use super::gcd::crt;
+use super::modint::{mul_mod, pow_mod, ModInt, ModIntBase};
+use super::prime::factorize;
+
+pub struct Combination<T: ModIntBase> {
+    fact: Vec<T>,
+    inv_fact: Vec<T>,
+}
+
+impl<T: ModIntBase> Combination<T> {
+    // The modulus must be a prime larger than `limit`.
+    pub fn new(limit: usize) -> Self {
+        let mut fact = vec![T::raw(1); limit + 1];
+        for i in 1..=limit {
+            fact[i] = fact[i - 1] * T::from(i);
+        }
+
+        let mut inv_fact = vec![T::raw(1); limit + 1];
+        inv_fact[limit] = fact[limit].inv();
+        for i in (1..=limit).rev() {
+            inv_fact[i - 1] = inv_fact[i] * T::from(i);
+        }
+
+        Combination { fact, inv_fact }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.fact.len() - 1
+    }
+
+    pub fn fact(&self, n: usize) -> T {
+        assert!(n <= self.limit(), "Value exceeds table limit");
+        self.fact[n]
+    }
+
+    pub fn inv_fact(&self, n: usize) -> T {
+        assert!(n <= self.limit(), "Value exceeds table limit");
+        self.inv_fact[n]
+    }
+
+    pub fn inv(&self, n: usize) -> T {
+        assert!(n >= 1, "Zero has no inverse");
+        self.inv_fact(n) * self.fact[n - 1]
+    }
+
+    // Invalid arguments (negative, or k > n) give zero. Valid arguments past
+    // the table limit panic instead, since the true value is not zero; the
+    // same precondition applies to perm, multinom and catalan.
+    pub fn binom(&self, n: i64, k: i64) -> T {
+        if n < 0 || k < 0 || k > n {
+            return T::raw(0);
+        }
+        let (n, k) = (n as usize, k as usize);
+        self.fact(n) * self.inv_fact[k] * self.inv_fact[n - k]
+    }
+
+    pub fn perm(&self, n: i64, k: i64) -> T {
+        if n < 0 || k < 0 || k > n {
+            return T::raw(0);
+        }
+        let (n, k) = (n as usize, k as usize);
+        self.fact(n) * self.inv_fact[n - k]
+    }
+
+    pub fn multinom(&self, counts: &[usize]) -> T {
+        let total = counts.iter().sum();
+        counts
+            .iter()
+            .fold(self.fact(total), |acc, &k| acc * self.inv_fact[k])
+    }
+
+    // Number of multisets of size r drawn from n kinds.
+    pub fn homogeneous(&self, n: i64, r: i64) -> T {
+        if n < 0 || r < 0 {
+            return T::raw(0);
+        }
+        if r == 0 {
+            return T::raw(1);
+        }
+        self.binom(n + r - 1, r)
+    }
+
+    pub fn catalan(&self, n: usize) -> T {
+        self.fact(2 * n) * self.inv_fact(n) * self.inv_fact(n + 1)
+    }
+
+    // S(n, k) for k = 0..=n via S(n, k) = sum_i (-1)^i / i! * (k - i)^n / (k - i)!.
+    pub fn stirling2_row(&self, n: usize) -> Vec<T> {
+        let signed: Vec<T> = (0..=n)
+            .map(|i| {
+                let x = self.inv_fact(i);
+                if i % 2 == 1 {
+                    -x
+                } else {
+                    x
+                }
+            })
+            .collect();
+        let powers: Vec<T> = (0..=n)
+            .map(|j| T::from(j).pow(n as u64) * self.inv_fact[j])
+            .collect();
+
+        let mut row = convolution_any_mod(&signed, &powers);
+        row.truncate(n + 1);
+        row
+    }
+}
+
+// Factorial tables of size p or p^e are only built up to this size.
+const MAX_TABLE_SIZE: u64 = 1 << 24;
+
+// Lucas's theorem with the O(p) factorial table kept between queries, so p
+// should be small.
+pub struct LucasCombination {
+    p: u64,
+    fact: Vec<u64>,
+    inv_fact: Vec<u64>,
+}
+
+impl LucasCombination {
+    pub fn new(p: u64) -> Self {
+        assert!(p >= 2, "Modulus must be prime");
+        let size = p as usize;
+        let mut fact = vec![1u64; size];
+        for i in 1..size {
+            fact[i] = mul_mod(fact[i - 1], i as u64, p);
+        }
+
+        let mut inv_fact = vec![1u64; size];
+        inv_fact[size - 1] = pow_mod(fact[size - 1], p - 2, p);
+        for i in (1..size).rev() {
+            inv_fact[i - 1] = mul_mod(inv_fact[i], i as u64, p);
+        }
+
+        LucasCombination { p, fact, inv_fact }
+    }
+
+    pub fn modulus(&self) -> u64 {
+        self.p
+    }
+
+    pub fn binom(&self, n: u64, k: u64) -> u64 {
+        if k > n {
+            return 0;
+        }
+
+        let p = self.p;
+        let (mut n, mut k) = (n, k);
+        let mut result = 1 % p;
+        while k > 0 {
+            let (ni, ki) = ((n % p) as usize, (k % p) as usize);
+            if ki > ni {
+                return 0;
+            }
+            let digit = mul_mod(self.fact[ni], self.inv_fact[ki], p);
+            result = mul_mod(result, mul_mod(digit, self.inv_fact[ni - ki], p), p);
+            n /= p;
+            k /= p;
+        }
+        result
+    }
+}
+
+// Picks whichever is cheaper: the multiplicative formula on every Lucas digit,
+// costing the sum of min(k_i, n_i - k_i), or an O(p) factorial table. Build a
+// LucasCombination instead when running many queries against one small p.
+pub fn binom_mod_prime(n: u64, k: u64, p: u64) -> u64 {
+    if k > n {
+        return 0;
+    }
+    match lucas_digit_work(n, k, p) {
+        None => 0,
+        Some(work) if work < p || p > MAX_TABLE_SIZE => binom_mod_prime_direct(n, k, p),
+        Some(_) => LucasCombination::new(p).binom(n, k),
+    }
+}
+
+// Sum of min(k_i, n_i - k_i) over the base-p digits, or None when some digit
+// of k exceeds the digit of n and the binomial vanishes mod p.
+fn lucas_digit_work(mut n: u64, mut k: u64, p: u64) -> Option<u64> {
+    let mut work = 0u64;
+    while k > 0 {
+        let (ni, ki) = (n % p, k % p);
+        if ki > ni {
+            return None;
+        }
+        work = work.saturating_add(ki.min(ni - ki));
+        n /= p;
+        k /= p;
+    }
+    Some(work)
+}
+
+// Lucas's theorem where each digit is evaluated in O(min(k_i, n_i - k_i)) by
+// the multiplicative formula; n < p is the single-digit case.
+fn binom_mod_prime_direct(n: u64, k: u64, p: u64) -> u64 {
+    let (mut n, mut k) = (n, k);
+    let mut result = 1 % p;
+    while k > 0 {
+        let (ni, ki) = (n % p, k % p);
+        if ki > ni {
+            return 0;
+        }
+        let r = ki.min(ni - ki);
+        let (mut numerator, mut denominator) = (1 % p, 1 % p);
+        for i in 0..r {
+            numerator = mul_mod(numerator, ni - i, p);
+            denominator = mul_mod(denominator, i + 1, p);
+        }
+        let digit = mul_mod(numerator, pow_mod(denominator, p - 2, p), p);
+        result = mul_mod(result, digit, p);
+        n /= p;
+        k /= p;
+    }
+    result
+}
+
+// Computes C(n, k) mod p^e by splitting it into its power of p and its p-free
+// part. The p-free part comes from the multiplicative formula in
+// O(min(k, n - k)) or, when p^e is smaller and fits a table, from the p-free
+// factorials evaluated blockwise with an O(p^e) table.
+pub fn binom_mod_prime_power(n: u64, k: u64, p: u64, e: u32) -> u64 {
+    let modulus = p.pow(e);
+    if k > n {
+        return 0;
+    }
+    if modulus == 1 {
+        return 0;
+    }
+
+    let valuation = legendre(n, p) - legendre(k, p) - legendre(n - k, p);
+    if valuation >= e as u64 {
+        return 0;
+    }
+    if e == 1 {
+        return binom_mod_prime(n, k, p);
+    }
+    let r = k.min(n - k);
+    if r < modulus || modulus > MAX_TABLE_SIZE {
+        return binom_mod_prime_power_direct(n, r, p, modulus, valuation);
+    }
+
+    let mut table = vec![1u64; modulus as usize + 1];
+    for i in 1..=modulus as usize {
+        table[i] = if (i as u64).is_multiple_of(p) {
+            table[i - 1]
+        } else {
+            mul_mod(table[i - 1], i as u64, modulus)
+        };
+    }
+    let p_free_factorial = |mut n: u64| {
+        let mut result = 1 % modulus;
+        while n > 0 {
+            result = mul_mod(
+                result,
+                pow_mod(table[modulus as usize], n / modulus, modulus),
+                modulus,
+            );
+            result = mul_mod(result, table[(n % modulus) as usize], modulus);
+            n /= p;
+        }
+        ModInt::new(result as i64, modulus as i64)
+    };
+
+    let numerator = p_free_factorial(n);
+    let denominator = p_free_factorial(k).mul(&p_free_factorial(n - k));
+    let power = ModInt::new(p as i64, modulus as i64).pow(valuation as i64);
+    numerator.mul(&denominator.inv()).mul(&power).value() as u64
+}
+
+// C(n, r) as the product of (n - r + i) / i over 1..=r with every factor of p
+// stripped from each term; the stripped power is the known valuation.
+fn binom_mod_prime_power_direct(n: u64, r: u64, p: u64, modulus: u64, valuation: u64) -> u64 {
+    let (mut numerator, mut denominator) = (1 % modulus, 1 % modulus);
+    for i in 1..=r {
+        let (mut a, mut b) = (n - r + i, i);
+        while a.is_multiple_of(p) {
+            a /= p;
+        }
+        while b.is_multiple_of(p) {
+            b /= p;
+        }
+        numerator = mul_mod(numerator, a, modulus);
+        denominator = mul_mod(denominator, b, modulus);
+    }
+
+    // Units mod p^e have order dividing phi(p^e).
+    let phi = modulus / p * (p - 1);
+    let inverse = pow_mod(denominator, phi - 1, modulus);
+    let power = pow_mod(p, valuation, modulus);
+    mul_mod(mul_mod(numerator, inverse, modulus), power, modulus)
+}
+
+// Exponent of p in n!.
+fn legendre(mut n: u64, p: u64) -> u64 {
+    let mut count = 0;
+    while n > 0 {
+        n /= p;
+        count += n;
+    }
+    count
+}
+
+pub fn binom_mod_any(n: u64, k: u64, m: u64) -> u64 {
+    let congruences: Vec<(i64, i64)> = factorize(m)
+        .into_iter()
+        .map(|(p, e)| (binom_mod_prime_power(n, k, p, e) as i64, p.pow(e) as i64))
+        .collect();
+    crt(&congruences).unwrap().0 as u64
+}
@@ -0,0 +1,306 @@
+use super::modint::{is_prime_u32, ModIntBase, StaticModInt};
+
+const NAIVE_THRESHOLD: usize = 60;
+
+const fn pow_mod_const(mut base: u64, mut exp: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    base %= m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    result
+}
+
+// Smallest primitive root of a prime m, or 0 when m is not prime.
+const fn primitive_root_const(m: u32) -> u32 {
+    if !is_prime_u32(m) {
+        return 0;
+    }
+    let m = m as u64;
+    let mut factors = [0u64; 32];
+    let mut count = 0;
+    let mut rest = m - 1;
+    let mut p = 2;
+    while p * p <= rest {
+        if rest.is_multiple_of(p) {
+            factors[count] = p;
+            count += 1;
+            while rest.is_multiple_of(p) {
+                rest /= p;
+            }
+        }
+        p += 1;
+    }
+    if rest > 1 {
+        factors[count] = rest;
+        count += 1;
+    }
+
+    let mut g = 1;
+    loop {
+        g += 1;
+        let mut i = 0;
+        while i < count && pow_mod_const(g, (m - 1) / factors[i], m) != 1 {
+            i += 1;
+        }
+        if i == count {
+            return g as u32;
+        }
+    }
+}
+
+struct NttInfo<const M: u32>;
+
+impl<const M: u32> NttInfo<M> {
+    const PRIMITIVE_ROOT: u32 = primitive_root_const(M);
+}
+
+// A primitive n-th root of unity; n must divide M - 1.
+pub(crate) fn root_of_unity<const M: u32>(n: usize) -> StaticModInt<M> {
+    StaticModInt::raw(NttInfo::<M>::PRIMITIVE_ROOT).pow((M as u64 - 1) / n as u64)
+}
+
+// roots[half + k] = w^k for the primitive (2 * half)-th root of unity w, so
+// every butterfly level reads one contiguous slice.
+fn roots_of_unity<const M: u32>(n: usize, invert: bool) -> Vec<StaticModInt<M>> {
+    let half = n / 2;
+    let mut roots = vec![StaticModInt::<M>::raw(1); n];
+    if half == 0 {
+        return roots;
+    }
+
+    let mut w = root_of_unity::<M>(n);
+    if invert {
+        w = w.inv();
+    }
+    for k in 1..half {
+        roots[half + k] = roots[half + k - 1] * w;
+    }
+    for i in (1..half).rev() {
+        roots[i] = roots[2 * i];
+    }
+    roots
+}
+
+pub(crate) fn ntt<const M: u32>(a: &mut [StaticModInt<M>], invert: bool) {
+    let n = a.len();
+    assert!(NttInfo::<M>::PRIMITIVE_ROOT != 0, "Modulus must be prime");
+    assert!(
+        n.is_power_of_two() && (M as u64 - 1).is_multiple_of(n as u64),
+        "Length is not supported by the modulus"
+    );
+
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j ^= bit;
+        if i < j {
+            a.swap(i, j);
+        }
+    }
+
+    let roots = roots_of_unity::<M>(n, invert);
+    let mut len = 2;
+    while len <= n {
+        let half = len / 2;
+        let twiddles = &roots[half..len];
+        for chunk in a.chunks_mut(len) {
+            let (lo, hi) = chunk.split_at_mut(half);
+            for ((x, y), &w) in lo.iter_mut().zip(hi.iter_mut()).zip(twiddles) {
+                let u = *x;
+                let v = *y * w;
+                *x = u + v;
+                *y = u - v;
+            }
+        }
+        len <<= 1;
+    }
+
+    if invert {
+        let inv_n = StaticModInt::<M>::from(n).inv();
+        for x in a.iter_mut() {
+            *x *= inv_n;
+        }
+    }
+}
+
+fn convolution_naive<const M: u32>(
+    a: &[StaticModInt<M>],
+    b: &[StaticModInt<M>],
+) -> Vec<StaticModInt<M>> {
+    let mut result = vec![StaticModInt::<M>::raw(0); a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            result[i + j] += x * y;
+        }
+    }
+    result
+}
+
+pub fn convolution<const M: u32>(
+    a: &[StaticModInt<M>],
+    b: &[StaticModInt<M>],
+) -> Vec<StaticModInt<M>> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    if a.len().min(b.len()) <= NAIVE_THRESHOLD {
+        return convolution_naive(a, b);
+    }
+
+    let len = a.len() + b.len() - 1;
+    let size = len.next_power_of_two();
+
+    let mut fa = a.to_vec();
+    let mut fb = b.to_vec();
+    fa.resize(size, StaticModInt::raw(0));
+    fb.resize(size, StaticModInt::raw(0));
+
+    ntt(&mut fa, false);
+    ntt(&mut fb, false);
+    for (x, y) in fa.iter_mut().zip(&fb) {
+        *x *= *y;
+    }
+    ntt(&mut fa, true);
+
+    fa.truncate(len);
+    fa
+}
+
+fn convolution_as<const M: u32>(a: &[i64], b: &[i64]) -> Vec<StaticModInt<M>> {
+    let a: Vec<StaticModInt<M>> = a.iter().map(|&x| StaticModInt::new(x)).collect();
+    let b: Vec<StaticModInt<M>> = b.iter().map(|&x| StaticModInt::new(x)).collect();
+    convolution(&a, &b)
+}
+
+const M1: u32 = 754_974_721;
+const M2: u32 = 167_772_161;
+const M3: u32 = 469_762_049;
+const M1_INV_MOD_M2: u64 = pow_mod_const(M1 as u64, M2 as u64 - 2, M2 as u64);
+const M1M2_INV_MOD_M3: u64 =
+    pow_mod_const(M1 as u64 * M2 as u64 % M3 as u64, M3 as u64 - 2, M3 as u64);
+
+// Mixed-radix digits (d0, d1, d2) of each coefficient x, so that
+// x = d0 + d1 * M1 + d2 * M1 * M2 with every digit below its modulus.
+fn convolution_three_primes(a: &[i64], b: &[i64]) -> Vec<(u64, u64, u64)> {
+    let c1 = convolution_as::<M1>(a, b);
+    let c2 = convolution_as::<M2>(a, b);
+    let c3 = convolution_as::<M3>(a, b);
+    let (m1, m2, m3) = (M1 as u64, M2 as u64, M3 as u64);
+
+    c1.iter()
+        .zip(&c2)
+        .zip(&c3)
+        .map(|((x1, x2), x3)| {
+            let d0 = x1.value() as u64;
+            let d1 = (x2.value() as u64 + m2 - d0 % m2) * M1_INV_MOD_M2 % m2;
+            let low = (d0 + m1 * d1) % m3;
+            let d2 = (x3.value() as u64 + m3 - low) * M1M2_INV_MOD_M3 % m3;
+            (d0, d1, d2)
+        })
+        .collect()
+}
+
+// Exact as long as every coefficient of the result fits in i64.
+pub fn convolution_ll(a: &[i64], b: &[i64]) -> Vec<i64> {
+    const M1M2: u128 = M1 as u128 * M2 as u128;
+    const PRODUCT: u128 = M1M2 * M3 as u128;
+
+    convolution_three_primes(a, b)
+        .iter()
+        .map(|&(d0, d1, d2)| {
+            let x = d0 as u128 + d1 as u128 * M1 as u128 + d2 as u128 * M1M2;
+            if x > PRODUCT / 2 {
+                (x as i128 - PRODUCT as i128) as i64
+            } else {
+                x as i64
+            }
+        })
+        .collect()
+}
+
+// Work that can run over StaticModInt<M> once T's modulus is known to be the
+// NTT prime M.
+pub(crate) trait NttPrimeVisitor {
+    type Output;
+
+    fn visit<const M: u32>(self) -> Self::Output;
+}
+
+// Calls visitor.visit::<M>() when T's modulus is one of the static NTT primes
+// and supports transforms of the given length.
+pub(crate) fn visit_ntt_prime<T: ModIntBase, V: NttPrimeVisitor>(
+    size: usize,
+    visitor: V,
+) -> Option<V::Output> {
+    if !(T::modulus() as u64 - 1).is_multiple_of(size as u64) {
+        return None;
+    }
+    match T::modulus() {
+        998_244_353 => Some(visitor.visit::<998_244_353>()),
+        M1 => Some(visitor.visit::<M1>()),
+        M2 => Some(visitor.visit::<M2>()),
+        M3 => Some(visitor.visit::<M3>()),
+        _ => None,
+    }
+}
+
+// Runs a single NTT convolution when T's modulus is an NTT prime.
+struct ConvolutionCast<'a, T>(&'a [T], &'a [T]);
+
+impl<T: ModIntBase> NttPrimeVisitor for ConvolutionCast<'_, T> {
+    type Output = Vec<T>;
+
+    fn visit<const M: u32>(self) -> Vec<T> {
+        let cast = |v: &[T]| -> Vec<StaticModInt<M>> {
+            v.iter().map(|x| StaticModInt::raw(x.value())).collect()
+        };
+        convolution(&cast(self.0), &cast(self.1))
+            .iter()
+            .map(|x| T::raw(x.value()))
+            .collect()
+    }
+}
+
+// Exact coefficients are below min(|a|, |b|) * (m - 1)^2, which stays under
+// M1 * M2 * M3 for any 32-bit modulus m when the shorter input has at most
+// 2^21 terms.
+pub fn convolution_any_mod<T: ModIntBase>(a: &[T], b: &[T]) -> Vec<T> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    if a.len().min(b.len()) <= NAIVE_THRESHOLD {
+        let mut result = vec![T::raw(0); a.len() + b.len() - 1];
+        for (i, &x) in a.iter().enumerate() {
+            for (j, &y) in b.iter().enumerate() {
+                result[i + j] += x * y;
+            }
+        }
+        return result;
+    }
+
+    let size = (a.len() + b.len() - 1).next_power_of_two();
+    if let Some(result) = visit_ntt_prime::<T, _>(size, ConvolutionCast(a, b)) {
+        return result;
+    }
+
+    let m = T::modulus() as u64;
+    let m1 = M1 as u64 % m;
+    let m1m2 = M1 as u64 * M2 as u64 % m;
+    let a: Vec<i64> = a.iter().map(|x| x.value() as i64).collect();
+    let b: Vec<i64> = b.iter().map(|x| x.value() as i64).collect();
+
+    // Both products stay below 2^61, so the sum fits in u64.
+    convolution_three_primes(&a, &b)
+        .iter()
+        .map(|&(d0, d1, d2)| T::raw(((d0 + m1 * d1 + m1m2 * d2) % m) as u32))
+        .collect()
+}
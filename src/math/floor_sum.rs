@@ -0,0 +1,140 @@
+// Sum of floor((a * i + b) / m) for i in 0..n. Works for negative a and b;
+// intermediate values wrap, so the result is exact whenever it fits in i64.
+pub fn floor_sum(n: i64, m: i64, a: i64, b: i64) -> i64 {
+    assert!(n >= 0 && m >= 1, "Invalid arguments");
+    let mut answer: u64 = 0;
+    let (mut a, mut b) = (a, b);
+
+    if a < 0 {
+        let a2 = a.rem_euclid(m);
+        answer = answer.wrapping_sub(triangle(n as u64).wrapping_mul(((a2 - a) / m) as u64));
+        a = a2;
+    }
+    if b < 0 {
+        let b2 = b.rem_euclid(m);
+        answer = answer.wrapping_sub((n as u64).wrapping_mul(((b2 - b) / m) as u64));
+        b = b2;
+    }
+
+    answer.wrapping_add(floor_sum_unsigned(n as u64, m as u64, a as u64, b as u64)) as i64
+}
+
+// n * (n - 1) / 2 without overflowing the intermediate product.
+fn triangle(n: u64) -> u64 {
+    if n.is_multiple_of(2) {
+        (n / 2).wrapping_mul(n.wrapping_sub(1))
+    } else {
+        n.wrapping_mul((n - 1) / 2)
+    }
+}
+
+fn floor_sum_unsigned(mut n: u64, mut m: u64, mut a: u64, mut b: u64) -> u64 {
+    let mut answer: u64 = 0;
+    loop {
+        if a >= m {
+            answer = answer.wrapping_add(triangle(n).wrapping_mul(a / m));
+            a %= m;
+        }
+        if b >= m {
+            answer = answer.wrapping_add(n.wrapping_mul(b / m));
+            b %= m;
+        }
+
+        let y_max = a as u128 * n as u128 + b as u128;
+        if y_max < m as u128 {
+            break;
+        }
+        n = (y_max / m as u128) as u64;
+        b = (y_max % m as u128) as u64;
+        std::mem::swap(&mut m, &mut a);
+    }
+    answer
+}
+
+pub trait Monoid: Clone {
+    fn identity() -> Self;
+    fn combine(&self, other: &Self) -> Self;
+}
+
+fn monoid_pow<T: Monoid>(x: &T, mut exp: u64) -> T {
+    let mut result = T::identity();
+    let mut base = x.clone();
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result.combine(&base);
+        }
+        base = base.combine(&base);
+        exp >>= 1;
+    }
+    result
+}
+
+// Universal Euclidean algorithm: walking x = 1..=l along the line
+// y = floor((p * x + r) / q), emits `up` each time y increases and `right`
+// at every x, and returns the product of the emitted sequence.
+pub fn universal_euclid<T: Monoid>(p: u64, q: u64, r: u64, l: u64, up: &T, right: &T) -> T {
+    assert!(q >= 1, "Denominator must be positive");
+    let prefix = monoid_pow(up, r / q);
+    prefix.combine(&universal_euclid_inner(p, q, r % q, l, up, right))
+}
+
+fn universal_euclid_inner<T: Monoid>(p: u64, q: u64, r: u64, l: u64, up: &T, right: &T) -> T {
+    if l == 0 {
+        return T::identity();
+    }
+    if p >= q {
+        let merged = monoid_pow(up, p / q).combine(right);
+        return universal_euclid_inner(p % q, q, r, l, up, &merged);
+    }
+
+    let m = ((p as u128 * l as u128 + r as u128) / q as u128) as u64;
+    if m == 0 {
+        return monoid_pow(right, l);
+    }
+
+    let tail = l - ((q as u128 * m as u128 - r as u128 - 1) / p as u128) as u64;
+    monoid_pow(right, (q - r - 1) / p)
+        .combine(up)
+        .combine(&universal_euclid_inner(
+            q,
+            p,
+            (q - r - 1) % p,
+            m - 1,
+            right,
+            up,
+        ))
+        .combine(&monoid_pow(right, tail))
+}
+
+pub struct QuotientRanges {
+    n: u64,
+    // None once the block ending at n == u64::MAX has been yielded.
+    next: Option<u64>,
+}
+
+impl Iterator for QuotientRanges {
+    type Item = (u64, u64, u64);
+
+    fn next(&mut self) -> Option<(u64, u64, u64)> {
+        let l = self.next.filter(|&l| l <= self.n)?;
+        let q = self.n / l;
+        let r = self.n / q;
+        self.next = r.checked_add(1);
+        Some((q, l, r))
+    }
+}
+
+// Yields (q, l, r) such that n / i == q for every i in l..=r, covering
+// 1..=n in O(sqrt(n)) blocks.
+pub fn quotient_ranges(n: u64) -> QuotientRanges {
+    quotient_ranges_from(n, 1)
+}
+
+// Same as quotient_ranges but covers start..=n; the first block begins at start.
+pub fn quotient_ranges_from(n: u64, start: u64) -> QuotientRanges {
+    assert!(start >= 1, "Start must be positive");
+    QuotientRanges {
+        n,
+        next: Some(start),
+    }
+}
@@ -0,0 +1,266 @@
+use super::convolution::convolution;
+use super::modint::StaticModInt;
+use super::modular::sqrt_mod;
+use std::ops::{Add, Mul, Neg, Sub};
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct FormalPowerSeries<const M: u32> {
+    coeffs: Vec<StaticModInt<M>>,
+}
+
+impl<const M: u32> FormalPowerSeries<M> {
+    pub fn new(coeffs: Vec<StaticModInt<M>>) -> Self {
+        FormalPowerSeries { coeffs }
+    }
+
+    pub fn from_values(values: &[i64]) -> Self {
+        Self::new(values.iter().map(|&x| StaticModInt::new(x)).collect())
+    }
+
+    pub fn coeffs(&self) -> &[StaticModInt<M>] {
+        &self.coeffs
+    }
+
+    pub fn into_vec(self) -> Vec<StaticModInt<M>> {
+        self.coeffs
+    }
+
+    pub fn len(&self) -> usize {
+        self.coeffs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    pub fn coeff(&self, i: usize) -> StaticModInt<M> {
+        self.coeffs.get(i).copied().unwrap_or_default()
+    }
+
+    // Keeps exactly the first n coefficients, padding with zeros.
+    pub fn truncated(&self, n: usize) -> Self {
+        let mut coeffs: Vec<StaticModInt<M>> = self.coeffs.iter().take(n).copied().collect();
+        coeffs.resize(n, StaticModInt::raw(0));
+        Self::new(coeffs)
+    }
+
+    fn trim(mut self) -> Self {
+        while self.coeffs.last() == Some(&StaticModInt::raw(0)) {
+            self.coeffs.pop();
+        }
+        self
+    }
+
+    pub fn derivative(&self) -> Self {
+        Self::new(
+            self.coeffs
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(i, &c)| c * StaticModInt::from(i))
+                .collect(),
+        )
+    }
+
+    pub fn integral(&self) -> Self {
+        let n = self.coeffs.len();
+        let mut inverses = vec![StaticModInt::<M>::raw(1); n + 1];
+        for i in 2..=n {
+            inverses[i] = -inverses[M as usize % i] * StaticModInt::from(M as usize / i);
+        }
+
+        let mut coeffs = Vec::with_capacity(n + 1);
+        coeffs.push(StaticModInt::raw(0));
+        for (i, &c) in self.coeffs.iter().enumerate() {
+            coeffs.push(c * inverses[i + 1]);
+        }
+        Self::new(coeffs)
+    }
+
+    pub fn inv(&self, n: usize) -> Self {
+        let a0 = self.coeff(0);
+        assert_ne!(a0.value(), 0, "Constant term must be invertible");
+
+        let two = StaticModInt::<M>::raw(2);
+        let mut g = Self::new(vec![a0.inv()]);
+        let mut len = 1;
+        while len < n {
+            len *= 2;
+            let mut correction = -(&self.truncated(len) * &g).truncated(len);
+            correction.coeffs[0] += two;
+            g = (&g * &correction).truncated(len);
+        }
+        g.truncated(n)
+    }
+
+    pub fn log(&self, n: usize) -> Self {
+        assert_eq!(self.coeff(0).value(), 1, "Constant term must be one");
+        if n == 0 {
+            return Self::default();
+        }
+
+        let quotient = (&self.truncated(n).derivative() * &self.inv(n)).truncated(n - 1);
+        quotient.integral().truncated(n)
+    }
+
+    pub fn exp(&self, n: usize) -> Self {
+        assert_eq!(self.coeff(0).value(), 0, "Constant term must be zero");
+
+        let mut g = Self::new(vec![StaticModInt::raw(1)]);
+        let mut len = 1;
+        while len < n {
+            len *= 2;
+            let mut h = &self.truncated(len) - &g.log(len);
+            h.coeffs[0] += StaticModInt::raw(1);
+            g = (&g * &h).truncated(len);
+        }
+        g.truncated(n)
+    }
+
+    pub fn pow(&self, k: u64, n: usize) -> Self {
+        if k == 0 {
+            return Self::new(vec![StaticModInt::raw(1)]).truncated(n);
+        }
+
+        let d = match self.coeffs.iter().position(|c| c.value() != 0) {
+            Some(d) => d,
+            None => return Self::default().truncated(n),
+        };
+        if (d as u64).saturating_mul(k) >= n as u64 {
+            return Self::default().truncated(n);
+        }
+
+        let shift = d * k as usize;
+        let c = self.coeffs[d];
+        let c_inv = c.inv();
+        let normalized = Self::new(self.coeffs[d..].iter().map(|&x| x * c_inv).collect());
+        let scaled = normalized.log(n - shift) * StaticModInt::from(k);
+        let powered = scaled.exp(n - shift) * c.pow(k);
+
+        let mut coeffs = vec![StaticModInt::raw(0); shift];
+        coeffs.extend(powered.coeffs);
+        Self::new(coeffs)
+    }
+
+    pub fn sqrt(&self, n: usize) -> Option<Self> {
+        let d = match self.coeffs.iter().position(|c| c.value() != 0) {
+            Some(d) => d,
+            None => return Some(Self::default().truncated(n)),
+        };
+        if d % 2 == 1 {
+            return None;
+        }
+        if d / 2 >= n {
+            return Some(Self::default().truncated(n));
+        }
+
+        let shift = d / 2;
+        let m = n - shift;
+        let shifted = Self::new(self.coeffs[d..].to_vec());
+        let root = StaticModInt::from(sqrt_mod(shifted.coeffs[0].value() as u64, M as u64)?);
+
+        let inv2 = StaticModInt::<M>::raw(2).inv();
+        let mut g = Self::new(vec![root]);
+        let mut len = 1;
+        while len < m {
+            len *= 2;
+            let quotient = (&shifted.truncated(len) * &g.inv(len)).truncated(len);
+            g = (&g.truncated(len) + &quotient) * inv2;
+        }
+
+        let mut coeffs = vec![StaticModInt::raw(0); shift];
+        coeffs.extend(g.truncated(m).coeffs);
+        Some(Self::new(coeffs))
+    }
+
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        let f = self.clone().trim();
+        let g = divisor.clone().trim();
+        assert!(!g.is_empty(), "Division by zero polynomial");
+        if f.len() < g.len() {
+            return (Self::default(), f);
+        }
+
+        let k = f.len() - g.len() + 1;
+        let rev_f = Self::new(f.coeffs.iter().rev().copied().collect());
+        let rev_g = Self::new(g.coeffs.iter().rev().copied().collect());
+        let rev_q = (&rev_f.truncated(k) * &rev_g.inv(k)).truncated(k);
+        let q = Self::new(rev_q.coeffs.into_iter().rev().collect());
+
+        let r = (&f - &(&g * &q)).truncated(g.len() - 1).trim();
+        (q.trim(), r)
+    }
+
+    pub fn eval(&self, x: StaticModInt<M>) -> StaticModInt<M> {
+        self.coeffs
+            .iter()
+            .rev()
+            .fold(StaticModInt::raw(0), |acc, &c| acc * x + c)
+    }
+}
+
+impl<const M: u32> Add<&FormalPowerSeries<M>> for &FormalPowerSeries<M> {
+    type Output = FormalPowerSeries<M>;
+
+    fn add(self, rhs: &FormalPowerSeries<M>) -> FormalPowerSeries<M> {
+        let n = self.len().max(rhs.len());
+        FormalPowerSeries::new((0..n).map(|i| self.coeff(i) + rhs.coeff(i)).collect())
+    }
+}
+
+impl<const M: u32> Sub<&FormalPowerSeries<M>> for &FormalPowerSeries<M> {
+    type Output = FormalPowerSeries<M>;
+
+    fn sub(self, rhs: &FormalPowerSeries<M>) -> FormalPowerSeries<M> {
+        let n = self.len().max(rhs.len());
+        FormalPowerSeries::new((0..n).map(|i| self.coeff(i) - rhs.coeff(i)).collect())
+    }
+}
+
+impl<const M: u32> Mul<&FormalPowerSeries<M>> for &FormalPowerSeries<M> {
+    type Output = FormalPowerSeries<M>;
+
+    fn mul(self, rhs: &FormalPowerSeries<M>) -> FormalPowerSeries<M> {
+        FormalPowerSeries::new(convolution(&self.coeffs, &rhs.coeffs))
+    }
+}
+
+impl<const M: u32> Mul<StaticModInt<M>> for FormalPowerSeries<M> {
+    type Output = FormalPowerSeries<M>;
+
+    fn mul(mut self, rhs: StaticModInt<M>) -> FormalPowerSeries<M> {
+        for c in &mut self.coeffs {
+            *c *= rhs;
+        }
+        self
+    }
+}
+
+impl<const M: u32> Neg for FormalPowerSeries<M> {
+    type Output = FormalPowerSeries<M>;
+
+    fn neg(mut self) -> FormalPowerSeries<M> {
+        for c in &mut self.coeffs {
+            *c = -*c;
+        }
+        self
+    }
+}
+
+macro_rules! impl_fps_owned_binop {
+    ($($op:ident, $method:ident;)*) => {$(
+        impl<const M: u32> $op for FormalPowerSeries<M> {
+            type Output = FormalPowerSeries<M>;
+
+            fn $method(self, rhs: FormalPowerSeries<M>) -> FormalPowerSeries<M> {
+                $op::$method(&self, &rhs)
+            }
+        }
+    )*};
+}
+
+impl_fps_owned_binop! {
+    Add, add;
+    Sub, sub;
+    Mul, mul;
+}
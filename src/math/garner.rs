@@ -0,0 +1,49 @@
+use super::modint::ModInt;
+
+// Mixed-radix digits d_i with x = d_0 + d_1 m_0 + d_2 m_0 m_1 + ...
+fn garner_digits(residues: &[ModInt]) -> Vec<i64> {
+    let mut digits: Vec<i64> = Vec::with_capacity(residues.len());
+
+    for residue in residues {
+        let m = residue.modulus();
+        let mut prefix = ModInt::new(0, m);
+        let mut product = ModInt::new(1, m);
+
+        for (j, &digit) in digits.iter().enumerate() {
+            prefix = prefix.add(&product.mul(&ModInt::new(digit, m)));
+            product = product.mul(&ModInt::new(residues[j].modulus(), m));
+        }
+
+        let inv = product
+            .checked_inv()
+            .expect("Moduli must be pairwise coprime");
+        digits.push(residue.sub(&prefix).mul(&inv).value());
+    }
+
+    digits
+}
+
+pub fn garner(residues: &[ModInt], target: i64) -> i64 {
+    let digits = garner_digits(residues);
+    let mut result = ModInt::new(0, target);
+
+    for (residue, &digit) in residues.iter().zip(&digits).rev() {
+        let radix = ModInt::new(residue.modulus(), target);
+        result = result.mul(&radix).add(&ModInt::new(digit, target));
+    }
+
+    result.value()
+}
+
+pub fn garner_u128(residues: &[ModInt]) -> Option<u128> {
+    let digits = garner_digits(residues);
+    let mut result = 0u128;
+
+    for (residue, &digit) in residues.iter().zip(&digits).rev() {
+        result = result
+            .checked_mul(residue.modulus() as u128)?
+            .checked_add(digit as u128)?;
+    }
+
+    Some(result)
+}
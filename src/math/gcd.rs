@@ -1,11 +1,148 @@
-pub fn gcd(a: usize, b: usize) -> usize {
-    if b == 0 {
-        a
-    } else {
-        gcd(b, a % b)
-    }
+pub trait Integer: Copy + Eq + Ord {
+    fn gcd(self, other: Self) -> Self;
+    fn checked_lcm(self, other: Self) -> Option<Self>;
+}
+
+pub trait SignedInteger: Integer {
+    fn ext_gcd(self, other: Self) -> (Self, Self, Self);
+}
+
+macro_rules! impl_unsigned_integer {
+    ($($t:ty),*) => {$(
+        impl Integer for $t {
+            fn gcd(self, other: Self) -> Self {
+                let (mut a, mut b) = (self, other);
+                if a == 0 {
+                    return b;
+                }
+                if b == 0 {
+                    return a;
+                }
+
+                let shift = (a | b).trailing_zeros();
+                a >>= a.trailing_zeros();
+                loop {
+                    b >>= b.trailing_zeros();
+                    if a > b {
+                        std::mem::swap(&mut a, &mut b);
+                    }
+                    b -= a;
+                    if b == 0 {
+                        break;
+                    }
+                }
+                a << shift
+            }
+
+            fn checked_lcm(self, other: Self) -> Option<Self> {
+                if self == 0 || other == 0 {
+                    return Some(0);
+                }
+                (self / self.gcd(other)).checked_mul(other)
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_signed_integer {
+    ($($t:ty),*) => {$(
+        impl Integer for $t {
+            // The only unrepresentable result is 2^(BITS - 1), from gcd(MIN, 0)
+            // or gcd(MIN, MIN); use unsigned_abs first if that can occur.
+            fn gcd(self, other: Self) -> Self {
+                let g = self.unsigned_abs().gcd(other.unsigned_abs());
+                <$t>::try_from(g).expect("gcd overflowed")
+            }
+
+            fn checked_lcm(self, other: Self) -> Option<Self> {
+                let lcm = self.unsigned_abs().checked_lcm(other.unsigned_abs())?;
+                <$t>::try_from(lcm).ok()
+            }
+        }
+
+        impl SignedInteger for $t {
+            fn ext_gcd(self, other: Self) -> (Self, Self, Self) {
+                let (mut old_r, mut r) = (self, other);
+                let (mut old_x, mut x) = (1, 0);
+                let (mut old_y, mut y) = (0, 1);
+
+                while r != 0 {
+                    // Stop before the final step: MIN / -1 overflows, and so
+                    // can the discarded coefficients, which are +-other / g
+                    // and +-self / g.
+                    let rem = old_r.wrapping_rem(r);
+                    if rem == 0 {
+                        (old_r, old_x, old_y) = (r, x, y);
+                        break;
+                    }
+                    let q = old_r / r;
+                    (old_r, r) = (r, rem);
+                    (old_x, x) = (x, old_x - q * x);
+                    (old_y, y) = (y, old_y - q * y);
+                }
+
+                if old_r < 0 {
+                    (old_r.checked_neg().expect("gcd overflowed"), -old_x, -old_y)
+                } else {
+                    (old_r, old_x, old_y)
+                }
+            }
+        }
+    )*};
 }
 
-pub fn lcm(a: usize, b: usize) -> usize {
-    a / gcd(a, b) * b
+impl_unsigned_integer!(u8, u16, u32, u64, u128, usize);
+impl_signed_integer!(i8, i16, i32, i64, i128, isize);
+
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    a.gcd(b)
+}
+
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    a.checked_lcm(b).expect("lcm overflowed")
+}
+
+pub fn checked_lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    a.checked_lcm(b)
+}
+
+pub fn ext_gcd<T: SignedInteger>(a: T, b: T) -> (T, T, T) {
+    a.ext_gcd(b)
+}
+
+// Solves x = r_i (mod m_i) for every pair, returning (x, lcm of the moduli)
+// with 0 <= x < lcm, or None when the system is inconsistent or the lcm
+// does not fit in i64.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let (mut r0, mut m0) = (0i128, 1i128);
+
+    for &(r, m) in congruences {
+        assert!(m >= 1, "Modulus must be positive");
+        let (mut r1, mut m1) = ((r as i128).rem_euclid(m as i128), m as i128);
+        if m0 < m1 {
+            std::mem::swap(&mut r0, &mut r1);
+            std::mem::swap(&mut m0, &mut m1);
+        }
+        if m0 % m1 == 0 {
+            if r0 % m1 != r1 {
+                return None;
+            }
+            continue;
+        }
+
+        let (g, inv, _) = ext_gcd(m0, m1);
+        if (r1 - r0) % g != 0 {
+            return None;
+        }
+        let u1 = m1 / g;
+        let x = (r1 - r0) / g % u1 * inv % u1;
+        r0 += x * m0;
+        m0 *= u1;
+        if m0 > i64::MAX as i128 {
+            return None;
+        }
+        r0 = r0.rem_euclid(m0);
+    }
+
+    Some((r0 as i64, m0 as i64))
 }
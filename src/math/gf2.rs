@@ -0,0 +1,233 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitMatrix {
+    rows: usize,
+    cols: usize,
+    words: usize,
+    data: Vec<u64>,
+}
+
+impl BitMatrix {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        let words = cols.div_ceil(64);
+        BitMatrix {
+            rows,
+            cols,
+            words,
+            data: vec![0; rows * words],
+        }
+    }
+
+    pub fn from_vec(values: &[Vec<bool>]) -> Self {
+        let cols = values.first().map_or(0, |row| row.len());
+        assert!(
+            values.iter().all(|row| row.len() == cols),
+            "Rows must have the same length"
+        );
+
+        let mut matrix = Self::new(values.len(), cols);
+        for (i, row) in values.iter().enumerate() {
+            for (j, &bit) in row.iter().enumerate() {
+                matrix.set(i, j, bit);
+            }
+        }
+        matrix
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> bool {
+        assert!(i < self.rows && j < self.cols, "Index out of bounds");
+        self.data[i * self.words + j / 64] >> (j % 64) & 1 == 1
+    }
+
+    pub fn set(&mut self, i: usize, j: usize, value: bool) {
+        assert!(i < self.rows && j < self.cols, "Index out of bounds");
+        let word = &mut self.data[i * self.words + j / 64];
+        if value {
+            *word |= 1 << (j % 64);
+        } else {
+            *word &= !(1 << (j % 64));
+        }
+    }
+
+    fn xor_row(&mut self, src: usize, dst: usize) {
+        for w in 0..self.words {
+            self.data[dst * self.words + w] ^= self.data[src * self.words + w];
+        }
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        if a != b {
+            for w in 0..self.words {
+                self.data.swap(a * self.words + w, b * self.words + w);
+            }
+        }
+    }
+
+    // Reduces the matrix to reduced row echelon form in place and returns
+    // the pivot columns.
+    pub fn gaussian_elimination(&mut self) -> Vec<usize> {
+        let mut pivots = Vec::new();
+        let mut row = 0;
+
+        for col in 0..self.cols {
+            if row == self.rows {
+                break;
+            }
+            let pivot = match (row..self.rows).find(|&r| self.get(r, col)) {
+                Some(pivot) => pivot,
+                None => continue,
+            };
+            self.swap_rows(row, pivot);
+            for r in 0..self.rows {
+                if r != row && self.get(r, col) {
+                    self.xor_row(row, r);
+                }
+            }
+
+            pivots.push(col);
+            row += 1;
+        }
+        pivots
+    }
+
+    pub fn rank(&self) -> usize {
+        self.clone().gaussian_elimination().len()
+    }
+
+    // Returns one solution of Ax = b, or None when the system is inconsistent.
+    pub fn solve(&self, b: &[bool]) -> Option<Vec<bool>> {
+        assert_eq!(self.rows, b.len(), "Dimension mismatch");
+
+        let mut augmented = BitMatrix::new(self.rows, self.cols + 1);
+        for (i, &bit) in b.iter().enumerate() {
+            for j in 0..self.cols {
+                augmented.set(i, j, self.get(i, j));
+            }
+            augmented.set(i, self.cols, bit);
+        }
+
+        let pivots = augmented.gaussian_elimination();
+        if pivots.last() == Some(&self.cols) {
+            return None;
+        }
+
+        let mut x = vec![false; self.cols];
+        for (row, &col) in pivots.iter().enumerate() {
+            x[col] = augmented.get(row, self.cols);
+        }
+        Some(x)
+    }
+
+    // Returns a basis of { x | Ax = 0 }.
+    pub fn nullspace(&self) -> Vec<Vec<bool>> {
+        let mut reduced = self.clone();
+        let pivots = reduced.gaussian_elimination();
+
+        let mut is_pivot = vec![false; self.cols];
+        for &col in &pivots {
+            is_pivot[col] = true;
+        }
+
+        (0..self.cols)
+            .filter(|&free| !is_pivot[free])
+            .map(|free| {
+                let mut x = vec![false; self.cols];
+                x[free] = true;
+                for (row, &col) in pivots.iter().enumerate() {
+                    x[col] = reduced.get(row, free);
+                }
+                x
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct XorBasis {
+    basis: [u64; 64],
+    rank: usize,
+}
+
+impl Default for XorBasis {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl XorBasis {
+    pub fn new() -> Self {
+        XorBasis {
+            basis: [0; 64],
+            rank: 0,
+        }
+    }
+
+    fn reduce(&self, mut x: u64) -> u64 {
+        for bit in (0..64).rev() {
+            if x >> bit & 1 == 1 && self.basis[bit] != 0 {
+                x ^= self.basis[bit];
+            }
+        }
+        x
+    }
+
+    // Returns false when x is already spanned by the basis.
+    pub fn insert(&mut self, x: u64) -> bool {
+        let x = self.reduce(x);
+        if x == 0 {
+            return false;
+        }
+        self.basis[63 - x.leading_zeros() as usize] = x;
+        self.rank += 1;
+        true
+    }
+
+    pub fn contains(&self, x: u64) -> bool {
+        self.reduce(x) == 0
+    }
+
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    pub fn max_xor(&self) -> u64 {
+        (0..64)
+            .rev()
+            .fold(0, |acc, bit| acc.max(acc ^ self.basis[bit]))
+    }
+
+    // Returns the k-th smallest (0-indexed) value among the 2^rank distinct
+    // xors of subsets, the empty subset giving 0.
+    pub fn kth_smallest(&self, k: u64) -> Option<u64> {
+        if self.rank < 64 && k >> self.rank != 0 {
+            return None;
+        }
+
+        let mut reduced = self.basis;
+        for i in 0..64 {
+            if reduced[i] == 0 {
+                continue;
+            }
+            for j in i + 1..64 {
+                if reduced[j] >> i & 1 == 1 {
+                    reduced[j] ^= reduced[i];
+                }
+            }
+        }
+
+        let vectors = reduced.iter().filter(|&&v| v != 0);
+        Some(
+            vectors
+                .enumerate()
+                .filter(|&(i, _)| k >> i & 1 == 1)
+                .fold(0, |acc, (_, &v)| acc ^ v),
+        )
+    }
+}
@@ -0,0 +1,196 @@
+use super::convolution::ntt;
+use super::fps::FormalPowerSeries;
+use super::modint::{ModIntBase, StaticModInt};
+
+const NAIVE_THRESHOLD: usize = 64;
+
+// Segment-tree layout: node i holds the product of (x - x_j) over its leaves,
+// padded leaves hold the constant 1.
+struct SubproductTree<const M: u32> {
+    size: usize,
+    len: usize,
+    nodes: Vec<FormalPowerSeries<M>>,
+}
+
+impl<const M: u32> SubproductTree<M> {
+    fn new(xs: &[StaticModInt<M>]) -> Self {
+        let size = xs.len().next_power_of_two();
+        let mut nodes = vec![FormalPowerSeries::new(vec![StaticModInt::raw(1)]); 2 * size];
+        for (i, &x) in xs.iter().enumerate() {
+            nodes[size + i] = FormalPowerSeries::new(vec![-x, StaticModInt::raw(1)]);
+        }
+        for i in (1..size).rev() {
+            nodes[i] = multiply_monic(&nodes[2 * i], &nodes[2 * i + 1]);
+        }
+
+        SubproductTree {
+            size,
+            len: xs.len(),
+            nodes,
+        }
+    }
+
+    // Product of (1 - x_j y) over the node's leaves, with its degree.
+    fn reversed(&self, node: usize) -> (Vec<StaticModInt<M>>, usize) {
+        let coeffs = self.nodes[node].coeffs();
+        (coeffs.iter().rev().copied().collect(), coeffs.len() - 1)
+    }
+
+    // Transposed evaluation: with rev(f) of length N, f(x_j) is the
+    // coefficient of y^(N - 1) in rev(f) / (1 - x_j y). Going down the tree
+    // only the top coefficients of rev(f) / Q_node are needed, and each child
+    // gets them from its parent's by a middle product with its sibling's Q.
+    fn evaluate(&self, f: &FormalPowerSeries<M>) -> Vec<StaticModInt<M>> {
+        let n = f.len().max(self.len);
+        let rev_f = FormalPowerSeries::new((0..n).rev().map(|k| f.coeff(k)).collect());
+        let (root, _) = self.reversed(1);
+        let quotient = (&rev_f * &FormalPowerSeries::new(root).inv(n)).truncated(n);
+
+        let mut values = vec![StaticModInt::raw(0); self.len];
+        self.evaluate_node(1, &quotient.coeffs()[n - self.len..], &mut values);
+        values
+    }
+
+    fn evaluate_node(&self, node: usize, top: &[StaticModInt<M>], values: &mut [StaticModInt<M>]) {
+        if top.is_empty() {
+            return;
+        }
+        if node >= self.size {
+            values[node - self.size] = top[0];
+            return;
+        }
+
+        if top.len() <= NAIVE_THRESHOLD {
+            for (child, sibling) in [(2 * node, 2 * node + 1), (2 * node + 1, 2 * node)] {
+                let (q, degree) = self.reversed(sibling);
+                let child_top: Vec<StaticModInt<M>> = (degree..top.len())
+                    .map(|i| (0..=degree).map(|t| top[i - t] * q[t]).sum())
+                    .collect();
+                self.evaluate_node(child, &child_top, values);
+            }
+            return;
+        }
+
+        // The wrapped part of a cyclic convolution of length >= top.len()
+        // only lands below index `degree`, which the middle product drops.
+        let size = top.len().next_power_of_two();
+        let mut top_ntt = top.to_vec();
+        top_ntt.resize(size, StaticModInt::raw(0));
+        ntt(&mut top_ntt, false);
+
+        for (child, sibling) in [(2 * node, 2 * node + 1), (2 * node + 1, 2 * node)] {
+            let (mut q, degree) = self.reversed(sibling);
+            if degree == top.len() {
+                continue;
+            }
+            q.resize(size, StaticModInt::raw(0));
+            ntt(&mut q, false);
+            for (x, &y) in q.iter_mut().zip(&top_ntt) {
+                *x *= y;
+            }
+            ntt(&mut q, true);
+            self.evaluate_node(child, &q[degree..top.len()], values);
+        }
+    }
+}
+
+// When the product degree d is a power of two, a cyclic convolution of
+// length d is exact except that the leading 1 wraps onto the constant term.
+fn multiply_monic<const M: u32>(
+    a: &FormalPowerSeries<M>,
+    b: &FormalPowerSeries<M>,
+) -> FormalPowerSeries<M> {
+    let degree = a.len() + b.len() - 2;
+    if a.len() < 2 || b.len() < 2 || degree <= NAIVE_THRESHOLD || !degree.is_power_of_two() {
+        return a * b;
+    }
+
+    let mut fa = a.coeffs().to_vec();
+    let mut fb = b.coeffs().to_vec();
+    fa.resize(degree, StaticModInt::raw(0));
+    fb.resize(degree, StaticModInt::raw(0));
+    ntt(&mut fa, false);
+    ntt(&mut fb, false);
+    for (x, &y) in fa.iter_mut().zip(&fb) {
+        *x *= y;
+    }
+    ntt(&mut fa, true);
+    fa[0] -= StaticModInt::raw(1);
+    fa.push(StaticModInt::raw(1));
+    FormalPowerSeries::new(fa)
+}
+
+pub fn multipoint_eval<const M: u32>(
+    f: &FormalPowerSeries<M>,
+    xs: &[StaticModInt<M>],
+) -> Vec<StaticModInt<M>> {
+    if xs.is_empty() {
+        return Vec::new();
+    }
+    SubproductTree::new(xs).evaluate(f)
+}
+
+// Returns the unique polynomial of degree < n through the n points (xs, ys).
+pub fn interpolate<const M: u32>(
+    xs: &[StaticModInt<M>],
+    ys: &[StaticModInt<M>],
+) -> FormalPowerSeries<M> {
+    assert_eq!(xs.len(), ys.len(), "Lengths must be the same");
+    if xs.is_empty() {
+        return FormalPowerSeries::default();
+    }
+
+    let tree = SubproductTree::new(xs);
+    let weights = tree.evaluate(&tree.nodes[1].derivative());
+
+    let mut values = vec![FormalPowerSeries::default(); 2 * tree.size];
+    for (i, (&y, &w)) in ys.iter().zip(&weights).enumerate() {
+        values[tree.size + i] = FormalPowerSeries::new(vec![y / w]);
+    }
+    for i in (1..tree.size).rev() {
+        values[i] =
+            &(&values[2 * i] * &tree.nodes[2 * i + 1]) + &(&values[2 * i + 1] * &tree.nodes[2 * i]);
+    }
+
+    values[1].truncated(xs.len())
+}
+
+// Evaluates at x the polynomial of degree < n taking the values ys at
+// 0, 1, ..., n - 1 in O(n). The modulus must be a prime larger than n.
+pub fn interpolate_consecutive<T: ModIntBase>(ys: &[T], x: u64) -> T {
+    let n = ys.len();
+    if x < n as u64 {
+        return ys[x as usize];
+    }
+    if n == 0 {
+        return T::raw(0);
+    }
+
+    let x = T::from(x);
+    let mut prefix = vec![T::raw(1); n + 1];
+    for i in 0..n {
+        prefix[i + 1] = prefix[i] * (x - T::from(i));
+    }
+    let mut suffix = vec![T::raw(1); n + 1];
+    for i in (0..n).rev() {
+        suffix[i] = suffix[i + 1] * (x - T::from(i));
+    }
+
+    let mut inv_fact = vec![T::raw(1); n];
+    let fact: T = (1..n).map(T::from).product();
+    inv_fact[n - 1] = fact.inv();
+    for i in (1..n).rev() {
+        inv_fact[i - 1] = inv_fact[i] * T::from(i);
+    }
+
+    let mut result = T::raw(0);
+    for (i, &y) in ys.iter().enumerate() {
+        let term = y * prefix[i] * suffix[i + 1] * inv_fact[i] * inv_fact[n - 1 - i];
+        if (n - 1 - i) % 2 == 1 {
+            result -= term;
+        } else {
+            result += term;
+        }
+    }
+    result
+}
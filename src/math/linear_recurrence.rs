@@ -0,0 +1,198 @@
+use super::convolution::{
+    convolution_any_mod, ntt, root_of_unity, visit_ntt_prime, NttPrimeVisitor,
+};
+use super::modint::{ModIntBase, StaticModInt};
+
+// Returns the shortest c with a_i = c_0 a_{i-1} + c_1 a_{i-2} + ... for
+// every i >= c.len(). The modulus must be prime.
+pub fn berlekamp_massey<T: ModIntBase>(sequence: &[T]) -> Vec<T> {
+    let zero = T::raw(0);
+    let mut current = vec![T::raw(1)];
+    let mut previous = vec![T::raw(1)];
+    let mut length = 0;
+    let mut shift = 1;
+    let mut last_discrepancy = T::raw(1);
+
+    for n in 0..sequence.len() {
+        let discrepancy = current
+            .iter()
+            .take(length + 1)
+            .enumerate()
+            .fold(zero, |acc, (i, &c)| acc + c * sequence[n - i]);
+        if discrepancy == zero {
+            shift += 1;
+            continue;
+        }
+
+        let coef = discrepancy / last_discrepancy;
+        let snapshot = current.clone();
+        if current.len() < previous.len() + shift {
+            current.resize(previous.len() + shift, zero);
+        }
+        for (i, &b) in previous.iter().enumerate() {
+            current[i + shift] -= coef * b;
+        }
+
+        if 2 * length <= n {
+            length = n + 1 - length;
+            previous = snapshot;
+            last_discrepancy = discrepancy;
+            shift = 1;
+        } else {
+            shift += 1;
+        }
+    }
+
+    current.resize(length + 1, zero);
+    current[1..].iter().map(|&c| -c).collect()
+}
+
+fn even_or_odd<T: ModIntBase>(poly: &[T], parity: usize) -> Vec<T> {
+    poly.iter().skip(parity).step_by(2).copied().collect()
+}
+
+// Computes [x^k] P(x) / Q(x) with the Bostan-Mori algorithm.
+pub fn bostan_mori<T: ModIntBase>(numerator: &[T], denominator: &[T], k: u64) -> T {
+    assert!(
+        denominator.first().is_some_and(|&q| q != T::raw(0)),
+        "Constant term must be invertible"
+    );
+
+    let size = ntt_length(numerator.len(), denominator.len());
+    let visitor = BostanMoriCast(numerator, denominator, k);
+    if let Some(result) = visit_ntt_prime::<T, _>(size, visitor) {
+        return result;
+    }
+
+    let mut p = numerator.to_vec();
+    let mut q = denominator.to_vec();
+    let mut k = k;
+
+    while k > 0 {
+        let q_neg: Vec<T> = q
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| if i % 2 == 1 { -c } else { c })
+            .collect();
+        let u = convolution_any_mod(&p, &q_neg);
+        let v = convolution_any_mod(&q, &q_neg);
+        p = even_or_odd(&u, (k % 2) as usize);
+        q = even_or_odd(&v, 0);
+        k >>= 1;
+    }
+
+    p.first().copied().unwrap_or(T::raw(0)) / q[0]
+}
+
+// Both polynomials keep at most max(|P|, |Q|) coefficients after every step,
+// so twice that bounds the length of P(x)Q(-x) and Q(x)Q(-x) throughout.
+fn ntt_length(p_len: usize, q_len: usize) -> usize {
+    (2 * p_len.max(q_len)).next_power_of_two()
+}
+
+struct BostanMoriCast<'a, T>(&'a [T], &'a [T], u64);
+
+impl<T: ModIntBase> NttPrimeVisitor for BostanMoriCast<'_, T> {
+    type Output = T;
+
+    fn visit<const M: u32>(self) -> T {
+        let cast = |v: &[T]| -> Vec<StaticModInt<M>> {
+            v.iter().map(|x| StaticModInt::raw(x.value())).collect()
+        };
+        T::raw(bostan_mori_ntt(&cast(self.0), &cast(self.1), self.2).value())
+    }
+}
+
+// Bostan-Mori that stays in evaluation form at a fixed NTT length n. With
+// h = n / 2 and w a primitive n-th root of unity, Q(-w^i) = Q(w^(i + h)), so
+// one transform of Q gives both Q(x) and Q(-x). The size-h transforms of the
+// even and odd parts then come from pairing i with i + h, and only a
+// size-h inverse and forward transform are needed to double them back.
+fn bostan_mori_ntt<const M: u32>(
+    numerator: &[StaticModInt<M>],
+    denominator: &[StaticModInt<M>],
+    mut k: u64,
+) -> StaticModInt<M> {
+    let zero = StaticModInt::<M>::raw(0);
+    if k == 0 {
+        return numerator.first().copied().unwrap_or(zero) / denominator[0];
+    }
+
+    let n = ntt_length(numerator.len(), denominator.len());
+    let h = n / 2;
+    let w = root_of_unity::<M>(n);
+    let mut powers = vec![StaticModInt::<M>::raw(1); n];
+    for i in 1..n {
+        powers[i] = powers[i - 1] * w;
+    }
+    let inv2 = StaticModInt::<M>::raw(2).inv();
+
+    let transform = |poly: &[StaticModInt<M>]| {
+        let mut values = poly.to_vec();
+        values.resize(n, zero);
+        ntt(&mut values, false);
+        values
+    };
+    // Values at w^(2i) to values at every w^i.
+    let double = |even: Vec<StaticModInt<M>>| {
+        let mut odd = even.clone();
+        ntt(&mut odd, true);
+        for (c, &p) in odd.iter_mut().zip(&powers) {
+            *c *= p;
+        }
+        ntt(&mut odd, false);
+        even.into_iter()
+            .zip(odd)
+            .flat_map(|(e, o)| [e, o])
+            .collect::<Vec<_>>()
+    };
+
+    let mut p = transform(numerator);
+    let mut q = transform(denominator);
+    loop {
+        let mut next_p = Vec::with_capacity(h);
+        let mut next_q = Vec::with_capacity(h);
+        for i in 0..h {
+            let (a, b) = (q[i], q[i + h]);
+            next_q.push(a * b);
+            let (x, y) = (p[i] * b, p[i + h] * a);
+            next_p.push(if k & 1 == 0 {
+                (x + y) * inv2
+            } else {
+                (x - y) * inv2 * powers[(n - i) % n]
+            });
+        }
+        k >>= 1;
+
+        if k == 0 {
+            // Constant terms are the averages of the values, and the 1 / h
+            // factors cancel.
+            let p0: StaticModInt<M> = next_p.iter().sum();
+            let q0: StaticModInt<M> = next_q.iter().sum();
+            return p0 / q0;
+        }
+        p = double(next_p);
+        q = double(next_q);
+    }
+}
+
+// Returns a_k for a_i = c_0 a_{i-1} + c_1 a_{i-2} + ..., given at least
+// c.len() initial terms.
+pub fn kth_term_of_linear_recurrence<T: ModIntBase>(initial: &[T], coeffs: &[T], k: u64) -> T {
+    if k < initial.len() as u64 {
+        return initial[k as usize];
+    }
+    let d = coeffs.len();
+    assert!(initial.len() >= d, "Not enough initial terms");
+    if d == 0 {
+        return T::raw(0);
+    }
+
+    let mut q = Vec::with_capacity(d + 1);
+    q.push(T::raw(1));
+    q.extend(coeffs.iter().map(|&c| -c));
+
+    let mut p = convolution_any_mod(&initial[..d], &q);
+    p.truncate(d);
+    bostan_mori(&p, &q, k)
+}
@@ -0,0 +1,356 @@
+use super::modint::ModIntBase;
+use std::ops::{Index, IndexMut, Mul};
+
+pub trait Semiring: Clone {
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn plus(&self, other: &Self) -> Self;
+    fn times(&self, other: &Self) -> Self;
+}
+
+impl<T: ModIntBase> Semiring for T {
+    fn zero() -> Self {
+        T::raw(0)
+    }
+
+    fn one() -> Self {
+        T::raw(1 % T::modulus())
+    }
+
+    fn plus(&self, other: &Self) -> Self {
+        *self + *other
+    }
+
+    fn times(&self, other: &Self) -> Self {
+        *self * *other
+    }
+}
+
+macro_rules! impl_semiring_for_primitive {
+    ($($t:ty),*) => {$(
+        impl Semiring for $t {
+            fn zero() -> Self {
+                0
+            }
+
+            fn one() -> Self {
+                1
+            }
+
+            fn plus(&self, other: &Self) -> Self {
+                self.wrapping_add(*other)
+            }
+
+            fn times(&self, other: &Self) -> Self {
+                self.wrapping_mul(*other)
+            }
+        }
+    )*};
+}
+
+impl_semiring_for_primitive!(i32, i64, i128, u32, u64, u128, usize);
+
+// (min, +) semiring; i64::MAX plays the role of +infinity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MinPlus(pub i64);
+
+impl Semiring for MinPlus {
+    fn zero() -> Self {
+        MinPlus(i64::MAX)
+    }
+
+    fn one() -> Self {
+        MinPlus(0)
+    }
+
+    fn plus(&self, other: &Self) -> Self {
+        MinPlus(self.0.min(other.0))
+    }
+
+    fn times(&self, other: &Self) -> Self {
+        if self.0 == i64::MAX || other.0 == i64::MAX {
+            MinPlus(i64::MAX)
+        } else {
+            MinPlus(self.0 + other.0)
+        }
+    }
+}
+
+// (max, +) semiring; i64::MIN plays the role of -infinity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MaxPlus(pub i64);
+
+impl Semiring for MaxPlus {
+    fn zero() -> Self {
+        MaxPlus(i64::MIN)
+    }
+
+    fn one() -> Self {
+        MaxPlus(0)
+    }
+
+    fn plus(&self, other: &Self) -> Self {
+        MaxPlus(self.0.max(other.0))
+    }
+
+    fn times(&self, other: &Self) -> Self {
+        if self.0 == i64::MIN || other.0 == i64::MIN {
+            MaxPlus(i64::MIN)
+        } else {
+            MaxPlus(self.0 + other.0)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+impl<T: Semiring> Matrix<T> {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Matrix {
+            rows,
+            cols,
+            data: vec![T::zero(); rows * cols],
+        }
+    }
+
+    pub fn from_vec(values: Vec<Vec<T>>) -> Self {
+        let rows = values.len();
+        let cols = values.first().map_or(0, |row| row.len());
+        assert!(
+            values.iter().all(|row| row.len() == cols),
+            "Rows must have the same length"
+        );
+
+        Matrix {
+            rows,
+            cols,
+            data: values.into_iter().flatten().collect(),
+        }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        let mut result = Self::new(n, n);
+        for i in 0..n {
+            result[(i, i)] = T::one();
+        }
+        result
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut result = Self::new(self.cols, self.rows);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                result[(j, i)] = self[(i, j)].clone();
+            }
+        }
+        result
+    }
+
+    pub fn pow(&self, exponent: u64) -> Self {
+        assert_eq!(self.rows, self.cols, "Matrix must be square");
+
+        let mut result = Self::identity(self.rows);
+        let mut base = self.clone();
+        let mut exp = exponent;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+            base = &base * &base;
+            exp >>= 1;
+        }
+        result
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        assert!(i < self.rows && j < self.cols, "Index out of bounds");
+        &self.data[i * self.cols + j]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
+        assert!(i < self.rows && j < self.cols, "Index out of bounds");
+        &mut self.data[i * self.cols + j]
+    }
+}
+
+impl<T: Semiring> Mul<&Matrix<T>> for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: &Matrix<T>) -> Matrix<T> {
+        assert_eq!(self.cols, rhs.rows, "Dimension mismatch");
+
+        let mut result = Matrix::<T>::new(self.rows, rhs.cols);
+        for i in 0..self.rows {
+            for k in 0..self.cols {
+                let a = &self.data[i * self.cols + k];
+                for j in 0..rhs.cols {
+                    let product = a.times(&rhs.data[k * rhs.cols + j]);
+                    let cell = &mut result.data[i * rhs.cols + j];
+                    *cell = cell.plus(&product);
+                }
+            }
+        }
+        result
+    }
+}
+
+impl<T: Semiring> Mul for Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: Matrix<T>) -> Matrix<T> {
+        &self * &rhs
+    }
+}
+
+impl<T: ModIntBase> Matrix<T> {
+    // Reduces the matrix to reduced row echelon form in place and returns
+    // the pivot columns. The modulus must be prime.
+    pub fn gaussian_elimination(&mut self) -> Vec<usize> {
+        let zero = T::raw(0);
+        let mut pivots = Vec::new();
+        let mut row = 0;
+
+        for col in 0..self.cols {
+            if row == self.rows {
+                break;
+            }
+            let pivot = match (row..self.rows).find(|&r| self[(r, col)] != zero) {
+                Some(pivot) => pivot,
+                None => continue,
+            };
+            self.swap_rows(row, pivot);
+
+            let inv = self[(row, col)].inv();
+            for j in col..self.cols {
+                self[(row, j)] *= inv;
+            }
+            for r in 0..self.rows {
+                let factor = self[(r, col)];
+                if r == row || factor == zero {
+                    continue;
+                }
+                for j in col..self.cols {
+                    let value = self[(row, j)];
+                    self[(r, j)] -= factor * value;
+                }
+            }
+
+            pivots.push(col);
+            row += 1;
+        }
+        pivots
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        if a != b {
+            for j in 0..self.cols {
+                self.data.swap(a * self.cols + j, b * self.cols + j);
+            }
+        }
+    }
+
+    pub fn rank(&self) -> usize {
+        self.clone().gaussian_elimination().len()
+    }
+
+    pub fn determinant(&self) -> T {
+        assert_eq!(self.rows, self.cols, "Matrix must be square");
+
+        let zero = T::raw(0);
+        let mut a = self.clone();
+        let mut det = T::raw(1);
+        for col in 0..a.cols {
+            let pivot = match (col..a.rows).find(|&r| a[(r, col)] != zero) {
+                Some(pivot) => pivot,
+                None => return zero,
+            };
+            if pivot != col {
+                a.swap_rows(col, pivot);
+                det = -det;
+            }
+
+            det *= a[(col, col)];
+            let inv = a[(col, col)].inv();
+            for r in col + 1..a.rows {
+                let factor = a[(r, col)] * inv;
+                if factor == zero {
+                    continue;
+                }
+                for j in col..a.cols {
+                    let value = a[(col, j)];
+                    a[(r, j)] -= factor * value;
+                }
+            }
+        }
+        det
+    }
+
+    pub fn inverse(&self) -> Option<Self> {
+        assert_eq!(self.rows, self.cols, "Matrix must be square");
+
+        let n = self.rows;
+        let mut augmented = Matrix::new(n, 2 * n);
+        for i in 0..n {
+            for j in 0..n {
+                augmented[(i, j)] = self[(i, j)];
+            }
+            augmented[(i, n + i)] = T::raw(1);
+        }
+
+        let pivots = augmented.gaussian_elimination();
+        if pivots.len() < n || pivots.last().is_some_and(|&c| c >= n) {
+            return None;
+        }
+
+        let mut result = Matrix::new(n, n);
+        for i in 0..n {
+            for j in 0..n {
+                result[(i, j)] = augmented[(i, n + j)];
+            }
+        }
+        Some(result)
+    }
+
+    // Returns one solution of Ax = b, or None when the system is inconsistent.
+    pub fn solve(&self, b: &[T]) -> Option<Vec<T>> {
+        assert_eq!(self.rows, b.len(), "Dimension mismatch");
+
+        let mut augmented = Matrix::new(self.rows, self.cols + 1);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                augmented[(i, j)] = self[(i, j)];
+            }
+            augmented[(i, self.cols)] = b[i];
+        }
+
+        let pivots = augmented.gaussian_elimination();
+        if pivots.last() == Some(&self.cols) {
+            return None;
+        }
+
+        let mut x = vec![T::raw(0); self.cols];
+        for (row, &col) in pivots.iter().enumerate() {
+            x[col] = augmented[(row, self.cols)];
+        }
+        Some(x)
+    }
+}
@@ -1,7 +1,35 @@
+pub mod bigint;
+pub mod combination;
+pub mod convolution;
+pub mod floor_sum;
+pub mod fps;
+pub mod garner;
 pub mod gcd;
+pub mod gf2;
+pub mod interpolation;
+pub mod linear_recurrence;
+pub mod matrix;
 pub mod modint;
+pub mod modular;
+pub mod multiplicative;
 pub mod prime;
+pub mod rational;
+pub mod sieve;
 
+pub use bigint::*;
+pub use combination::*;
+pub use convolution::*;
+pub use floor_sum::*;
+pub use fps::*;
+pub use garner::*;
 pub use gcd::*;
+pub use gf2::*;
+pub use interpolation::*;
+pub use linear_recurrence::*;
+pub use matrix::*;
 pub use modint::*;
+pub use modular::*;
+pub use multiplicative::*;
 pub use prime::*;
+pub use rational::*;
+pub use sieve::*;
@@ -1,3 +1,12 @@
+use super::gcd::ext_gcd;
+use std::fmt;
+use std::hash::Hash;
+use std::iter::{Product, Sum};
+use std::marker::PhantomData;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
 #[derive(Clone)]
 pub struct ModInt {
     value: i64,
@@ -6,23 +15,28 @@ pub struct ModInt {
 
 impl ModInt {
     pub fn new(value: i64, modulus: i64) -> Self {
-        let value = ((value % modulus) + modulus) % modulus;
+        let value = value.rem_euclid(modulus);
+        ModInt { value, modulus }
+    }
+
+    fn from_i128(value: i128, modulus: i64) -> Self {
+        let value = value.rem_euclid(modulus as i128) as i64;
         ModInt { value, modulus }
     }
 
     pub fn add(&self, other: &ModInt) -> ModInt {
         assert_eq!(self.modulus, other.modulus, "Moduli must be the same");
-        ModInt::new(self.value + other.value, self.modulus)
+        ModInt::from_i128(self.value as i128 + other.value as i128, self.modulus)
     }
 
     pub fn sub(&self, other: &ModInt) -> ModInt {
         assert_eq!(self.modulus, other.modulus, "Moduli must be the same");
-        ModInt::new(self.value - other.value, self.modulus)
+        ModInt::from_i128(self.value as i128 - other.value as i128, self.modulus)
     }
 
     pub fn mul(&self, other: &ModInt) -> ModInt {
         assert_eq!(self.modulus, other.modulus, "Moduli must be the same");
-        ModInt::new(self.value * other.value, self.modulus)
+        ModInt::from_i128(self.value as i128 * other.value as i128, self.modulus)
     }
 
     pub fn pow(&self, exponent: i64) -> ModInt {
@@ -41,7 +55,493 @@ impl ModInt {
         result
     }
 
+    pub fn inv(&self) -> ModInt {
+        self.checked_inv().expect("Value is not invertible")
+    }
+
+    pub fn checked_inv(&self) -> Option<ModInt> {
+        inv_mod(self.value, self.modulus).map(|inv| ModInt::new(inv, self.modulus))
+    }
+
+    // Fermat's little theorem; only valid when the modulus is prime.
+    pub fn inv_prime(&self) -> ModInt {
+        assert_ne!(self.value, 0, "Value is not invertible");
+        self.pow(self.modulus - 2)
+    }
+
+    pub fn div(&self, other: &ModInt) -> ModInt {
+        assert_eq!(self.modulus, other.modulus, "Moduli must be the same");
+        self.mul(&other.inv())
+    }
+
     pub fn value(&self) -> i64 {
         self.value
     }
+
+    pub fn modulus(&self) -> i64 {
+        self.modulus
+    }
+}
+
+pub fn mul_mod(a: u64, b: u64, modulus: u64) -> u64 {
+    (a as u128 * b as u128 % modulus as u128) as u64
+}
+
+pub fn pow_mod(base: u64, exponent: u64, modulus: u64) -> u64 {
+    let mut result = 1 % modulus;
+    let mut base = base % modulus;
+    let mut exp = exponent;
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, modulus);
+        }
+        base = mul_mod(base, base, modulus);
+        exp >>= 1;
+    }
+
+    result
+}
+
+fn inv_mod(value: i64, modulus: i64) -> Option<i64> {
+    let (g, x, _) = ext_gcd(value.rem_euclid(modulus) as i128, modulus as i128);
+    if g == 1 {
+        Some(x.rem_euclid(modulus as i128) as i64)
+    } else {
+        None
+    }
+}
+
+pub(crate) const fn is_prime_u32(n: u32) -> bool {
+    if n < 2 {
+        return false;
+    }
+    let n = n as u64;
+    let mut i = 2;
+    while i * i <= n {
+        if n.is_multiple_of(i) {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+pub trait ModIntBase:
+    Copy
+    + Eq
+    + Hash
+    + Default
+    + fmt::Display
+    + fmt::Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+    + Sum
+    + Product
+    + From<i64>
+    + From<u64>
+    + From<usize>
+    + FromStr
+{
+    fn modulus() -> u32;
+    fn raw(value: u32) -> Self;
+    fn new(value: i64) -> Self;
+    fn value(&self) -> u32;
+    fn pow(&self, exponent: u64) -> Self;
+    fn inv(&self) -> Self;
+}
+
+pub type ModInt998244353 = StaticModInt<998_244_353>;
+pub type ModInt1000000007 = StaticModInt<1_000_000_007>;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct StaticModInt<const M: u32> {
+    value: u32,
+}
+
+impl<const M: u32> StaticModInt<M> {
+    const IS_PRIME: bool = is_prime_u32(M);
+
+    pub fn raw(value: u32) -> Self {
+        StaticModInt { value }
+    }
+
+    pub fn modulus() -> u32 {
+        M
+    }
+
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+
+    pub fn inv(&self) -> Self {
+        if Self::IS_PRIME {
+            assert_ne!(self.value, 0, "Value is not invertible");
+            return self.pow(M as u64 - 2);
+        }
+        let inv = inv_mod(self.value as i64, M as i64).expect("Value is not invertible");
+        Self::raw(inv as u32)
+    }
+}
+
+impl<const M: u32> Mul for StaticModInt<M> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::raw((self.value as u64 * rhs.value as u64 % M as u64) as u32)
+    }
+}
+
+// set_modulus must not run while values under the old modulus are live or
+// while other threads compute with it; a concurrent reader could pair the new
+// modulus with the old inverse. Under that contract Relaxed accesses suffice.
+pub struct Barrett {
+    modulus: AtomicU32,
+    inverse: AtomicU64,
+}
+
+impl Barrett {
+    pub const fn new(modulus: u32) -> Self {
+        assert!(modulus >= 1, "Modulus must be positive");
+        Barrett {
+            modulus: AtomicU32::new(modulus),
+            inverse: AtomicU64::new(u64::MAX / modulus as u64),
+        }
+    }
+
+    pub fn set_modulus(&self, modulus: u32) {
+        assert!(modulus >= 1, "Modulus must be positive");
+        self.modulus.store(modulus, Ordering::Relaxed);
+        self.inverse
+            .store(u64::MAX / modulus as u64, Ordering::Relaxed);
+    }
+
+    pub fn modulus(&self) -> u32 {
+        self.modulus.load(Ordering::Relaxed)
+    }
+
+    // Computes a * b mod m for a, b < m without a 64-bit division. The
+    // estimated quotient is at most one below the true one, so a single
+    // correction step suffices for every 32-bit modulus.
+    pub fn mul(&self, a: u32, b: u32) -> u32 {
+        let m = self.modulus.load(Ordering::Relaxed) as u64;
+        let im = self.inverse.load(Ordering::Relaxed);
+
+        let z = a as u64 * b as u64;
+        let q = ((z as u128 * im as u128) >> 64) as u64;
+        let mut r = z - q * m;
+        if r >= m {
+            r -= m;
+        }
+        r as u32
+    }
+}
+
+pub trait ModIntId: 'static + Copy + Eq + Hash + Default {
+    fn barrett() -> &'static Barrett;
+}
+
+// Declares a new id whose modulus starts out as 998244353; call
+// DynamicModInt::<Id>::set_modulus before creating any values.
+#[macro_export]
+macro_rules! modint_id {
+    ($vis:vis $name:ident) => {
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+        $vis struct $name;
+
+        impl $crate::math::ModIntId for $name {
+            fn barrett() -> &'static $crate::math::Barrett {
+                static BARRETT: $crate::math::Barrett = $crate::math::Barrett::new(998_244_353);
+                &BARRETT
+            }
+        }
+    };
+}
+
+modint_id!(pub DefaultId);
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct DynamicModInt<I: ModIntId = DefaultId> {
+    value: u32,
+    phantom: PhantomData<I>,
+}
+
+impl<I: ModIntId> DynamicModInt<I> {
+    pub fn set_modulus(modulus: u32) {
+        I::barrett().set_modulus(modulus);
+    }
+
+    pub fn raw(value: u32) -> Self {
+        DynamicModInt {
+            value,
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn modulus() -> u32 {
+        I::barrett().modulus()
+    }
+
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+
+    pub fn inv(&self) -> Self {
+        let inv =
+            inv_mod(self.value as i64, Self::modulus() as i64).expect("Value is not invertible");
+        Self::raw(inv as u32)
+    }
+}
+
+impl<I: ModIntId> Mul for DynamicModInt<I> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::raw(I::barrett().mul(self.value, rhs.value))
+    }
 }
+
+macro_rules! impl_modint_ops {
+    ([$($generics:tt)*] $ty:ty) => {
+        impl<$($generics)*> $ty {
+            pub fn new(value: i64) -> Self {
+                Self::raw(value.rem_euclid(Self::modulus() as i64) as u32)
+            }
+
+            pub fn pow(&self, exponent: u64) -> Self {
+                let mut result = Self::raw(1 % Self::modulus());
+                let mut base = *self;
+                let mut exp = exponent;
+
+                while exp > 0 {
+                    if exp & 1 == 1 {
+                        result *= base;
+                    }
+                    base *= base;
+                    exp >>= 1;
+                }
+
+                result
+            }
+        }
+
+        impl<$($generics)*> ModIntBase for $ty {
+            fn modulus() -> u32 {
+                Self::modulus()
+            }
+
+            fn raw(value: u32) -> Self {
+                Self::raw(value)
+            }
+
+            fn new(value: i64) -> Self {
+                Self::new(value)
+            }
+
+            fn value(&self) -> u32 {
+                self.value()
+            }
+
+            fn pow(&self, exponent: u64) -> Self {
+                self.pow(exponent)
+            }
+
+            fn inv(&self) -> Self {
+                self.inv()
+            }
+        }
+
+        impl<$($generics)*> Add for $ty {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                let m = Self::modulus() as u64;
+                let sum = self.value as u64 + rhs.value as u64;
+                if sum >= m {
+                    Self::raw((sum - m) as u32)
+                } else {
+                    Self::raw(sum as u32)
+                }
+            }
+        }
+
+        impl<$($generics)*> Sub for $ty {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                if self.value >= rhs.value {
+                    Self::raw(self.value - rhs.value)
+                } else {
+                    let m = Self::modulus() as u64;
+                    Self::raw((self.value as u64 + m - rhs.value as u64) as u32)
+                }
+            }
+        }
+
+        impl<$($generics)*> Div for $ty {
+            type Output = Self;
+
+            #[allow(clippy::suspicious_arithmetic_impl)]
+            fn div(self, rhs: Self) -> Self {
+                self * rhs.inv()
+            }
+        }
+
+        impl<$($generics)*> Neg for $ty {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self::raw(0) - self
+            }
+        }
+
+        impl<$($generics)*> Neg for &$ty {
+            type Output = $ty;
+
+            fn neg(self) -> $ty {
+                -*self
+            }
+        }
+
+        impl_modint_ops!(@binop [$($generics)*] $ty, Add, add, AddAssign, add_assign);
+        impl_modint_ops!(@binop [$($generics)*] $ty, Sub, sub, SubAssign, sub_assign);
+        impl_modint_ops!(@binop [$($generics)*] $ty, Mul, mul, MulAssign, mul_assign);
+        impl_modint_ops!(@binop [$($generics)*] $ty, Div, div, DivAssign, div_assign);
+
+        impl<$($generics)*> Sum for $ty {
+            fn sum<It: Iterator<Item = Self>>(iter: It) -> Self {
+                iter.fold(Self::raw(0), |acc, x| acc + x)
+            }
+        }
+
+        impl<'a, $($generics)*> Sum<&'a $ty> for $ty {
+            fn sum<It: Iterator<Item = &'a Self>>(iter: It) -> Self {
+                iter.fold(Self::raw(0), |acc, x| acc + x)
+            }
+        }
+
+        impl<$($generics)*> Product for $ty {
+            fn product<It: Iterator<Item = Self>>(iter: It) -> Self {
+                iter.fold(Self::raw(1 % Self::modulus()), |acc, x| acc * x)
+            }
+        }
+
+        impl<'a, $($generics)*> Product<&'a $ty> for $ty {
+            fn product<It: Iterator<Item = &'a Self>>(iter: It) -> Self {
+                iter.fold(Self::raw(1 % Self::modulus()), |acc, x| acc * x)
+            }
+        }
+
+        impl<$($generics)*> From<i64> for $ty {
+            fn from(value: i64) -> Self {
+                Self::new(value)
+            }
+        }
+
+        impl<$($generics)*> From<u64> for $ty {
+            fn from(value: u64) -> Self {
+                Self::raw((value % Self::modulus() as u64) as u32)
+            }
+        }
+
+        impl<$($generics)*> From<usize> for $ty {
+            fn from(value: usize) -> Self {
+                Self::from(value as u64)
+            }
+        }
+
+        impl<$($generics)*> fmt::Display for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.value)
+            }
+        }
+
+        impl<$($generics)*> fmt::Debug for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.value)
+            }
+        }
+
+        impl<$($generics)*> FromStr for $ty {
+            type Err = ParseModIntError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let (negative, digits) = match s.strip_prefix('-') {
+                    Some(rest) => (true, rest),
+                    None => (false, s.strip_prefix('+').unwrap_or(s)),
+                };
+                if digits.is_empty() {
+                    return Err(ParseModIntError);
+                }
+
+                let m = Self::modulus() as u64;
+                let mut value = 0u64;
+                for c in digits.chars() {
+                    let digit = c.to_digit(10).ok_or(ParseModIntError)?;
+                    value = (value * 10 + digit as u64) % m;
+                }
+
+                let result = Self::raw(value as u32);
+                Ok(if negative { -result } else { result })
+            }
+        }
+    };
+    (@binop [$($generics:tt)*] $ty:ty, $op:ident, $method:ident, $op_assign:ident, $method_assign:ident) => {
+        impl<$($generics)*> $op<&$ty> for $ty {
+            type Output = $ty;
+
+            fn $method(self, rhs: &$ty) -> $ty {
+                $op::$method(self, *rhs)
+            }
+        }
+
+        impl<$($generics)*> $op<$ty> for &$ty {
+            type Output = $ty;
+
+            fn $method(self, rhs: $ty) -> $ty {
+                $op::$method(*self, rhs)
+            }
+        }
+
+        impl<$($generics)*> $op<&$ty> for &$ty {
+            type Output = $ty;
+
+            fn $method(self, rhs: &$ty) -> $ty {
+                $op::$method(*self, *rhs)
+            }
+        }
+
+        impl<$($generics)*> $op_assign for $ty {
+            fn $method_assign(&mut self, rhs: $ty) {
+                *self = $op::$method(*self, rhs);
+            }
+        }
+
+        impl<$($generics)*> $op_assign<&$ty> for $ty {
+            fn $method_assign(&mut self, rhs: &$ty) {
+                *self = $op::$method(*self, *rhs);
+            }
+        }
+    };
+}
+
+impl_modint_ops!([const M: u32] StaticModInt<M>);
+impl_modint_ops!([I: ModIntId] DynamicModInt<I>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseModIntError;
+
+impl fmt::Display for ParseModIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid digit found in string")
+    }
+}
+
+impl std::error::Error for ParseModIntError {}
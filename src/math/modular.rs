@@ -0,0 +1,110 @@
+use super::gcd::{ext_gcd, gcd};
+use super::modint::{mul_mod, pow_mod};
+use super::prime::primitive_root;
+use std::collections::HashMap;
+
+// Smallest x >= 0 with a^x = b (mod m), using baby-step giant-step after
+// dividing out the common factors of a and m.
+pub fn discrete_log(a: u64, b: u64, m: u64) -> Option<u64> {
+    assert!(m >= 1, "Modulus must be positive");
+    let (mut a, mut b, mut m) = (a % m, b % m, m);
+    let mut coef = 1 % m;
+    let mut offset = 0;
+
+    loop {
+        if coef == b {
+            return Some(offset);
+        }
+        let g = gcd(a, m);
+        if g == 1 {
+            break;
+        }
+        if !b.is_multiple_of(g) {
+            return None;
+        }
+        b /= g;
+        m /= g;
+        coef = mul_mod(coef, a / g, m);
+        a %= m;
+        offset += 1;
+    }
+
+    let n = m.isqrt() + 1;
+    let mut baby = HashMap::new();
+    let mut value = b;
+    for j in 0..n {
+        baby.insert(value, j);
+        value = mul_mod(value, a, m);
+    }
+
+    let giant = pow_mod(a, n, m);
+    let mut value = coef;
+    for i in 1..=n {
+        value = mul_mod(value, giant, m);
+        if let Some(&j) = baby.get(&value) {
+            return Some(offset + i * n - j);
+        }
+    }
+    None
+}
+
+// Tonelli-Shanks; returns the smaller of the two roots modulo the prime p.
+pub fn sqrt_mod(a: u64, p: u64) -> Option<u64> {
+    let a = a % p;
+    if a == 0 || p == 2 {
+        return Some(a);
+    }
+    if pow_mod(a, (p - 1) / 2, p) != 1 {
+        return None;
+    }
+
+    let s = (p - 1).trailing_zeros();
+    let q = (p - 1) >> s;
+    let z = (2..p)
+        .find(|&z| pow_mod(z, (p - 1) / 2, p) == p - 1)
+        .unwrap();
+
+    let mut m = s;
+    let mut c = pow_mod(z, q, p);
+    let mut t = pow_mod(a, q, p);
+    let mut r = pow_mod(a, q.div_ceil(2), p);
+    while t != 1 {
+        let mut i = 0;
+        let mut t2 = t;
+        while t2 != 1 {
+            t2 = mul_mod(t2, t2, p);
+            i += 1;
+        }
+        let b = pow_mod(c, 1 << (m - i - 1), p);
+        m = i;
+        c = mul_mod(b, b, p);
+        t = mul_mod(t, c, p);
+        r = mul_mod(r, b, p);
+    }
+    Some(r.min(p - r))
+}
+
+// Some x with x^k = a (mod p) for the prime p, found through a discrete
+// logarithm to a primitive root.
+pub fn kth_root_mod(a: u64, k: u64, p: u64) -> Option<u64> {
+    let a = a % p;
+    if k == 0 {
+        return if a == 1 % p { Some(1 % p) } else { None };
+    }
+    if a == 0 {
+        return Some(0);
+    }
+
+    let g = primitive_root(p);
+    let t = discrete_log(g, a, p)?;
+    let order = p - 1;
+    let d = gcd(k % order, order);
+    if !t.is_multiple_of(d) {
+        return None;
+    }
+
+    let modulus = (order / d) as i128;
+    let (_, inv, _) = ext_gcd((k / d) as i128 % modulus, modulus);
+    let y = ((t / d) as i128 * inv).rem_euclid(modulus) as u64;
+    Some(pow_mod(g, y, p))
+}
@@ -0,0 +1,169 @@
+use super::floor_sum::quotient_ranges;
+use super::interpolation::interpolate_consecutive;
+use super::modint::ModIntBase;
+use super::prime::lucy_table;
+use super::sieve::Sieve;
+use std::ops::{Add, Mul, Sub};
+
+fn du_sieve_limit(n: u64) -> usize {
+    ((n as f64).powf(2.0 / 3.0) as u64).clamp(1, n.max(1)) as usize
+}
+
+// Du's sieve for F(n) = f(1) + ... + f(n) when (f * 1)(i) has the known
+// prefix sum `convolved(v)`, using F(v) = convolved(v) - sum_{i>=2} F(v / i).
+// `prefix[v]` must hold F(v) for every v it covers.
+fn du_sieve<T, C>(n: u64, prefix: &[T], convolved: C) -> T
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + From<u32> + TryFrom<u64>,
+    C: Fn(u64) -> T,
+{
+    let limit = prefix.len() as u64 - 1;
+    if n <= limit {
+        return prefix[n as usize];
+    }
+
+    let count = (n / (limit + 1)) as usize;
+    let mut large = vec![T::from(0); count + 1];
+    for i in (1..=count).rev() {
+        let v = n / i as u64;
+        let mut sum = convolved(v);
+        for (q, l, r) in quotient_ranges(v).skip(1) {
+            let inner = if q <= limit {
+                prefix[q as usize]
+            } else {
+                large[i * l as usize]
+            };
+            let width = T::try_from(r - l + 1).ok().expect("Block width overflowed");
+            sum = sum - inner * width;
+        }
+        large[i] = sum;
+    }
+    large[1]
+}
+
+pub fn phi_prefix_sum(n: u64) -> u128 {
+    let sieve = Sieve::new(du_sieve_limit(n));
+    let mut prefix = vec![0u128; sieve.limit() + 1];
+    for (v, &phi) in sieve.phi().iter().enumerate().skip(1) {
+        prefix[v] = prefix[v - 1] + phi as u128;
+    }
+
+    du_sieve(n, &prefix, |v| {
+        let v = v as u128;
+        v * (v + 1) / 2
+    })
+}
+
+pub fn mobius_prefix_sum(n: u64) -> i64 {
+    let sieve = Sieve::new(du_sieve_limit(n));
+    let mut prefix = vec![0i64; sieve.limit() + 1];
+    for (v, &mu) in sieve.mobius().iter().enumerate().skip(1) {
+        prefix[v] = prefix[v - 1] + mu as i64;
+    }
+
+    du_sieve(n, &prefix, |v| i64::from(v >= 1))
+}
+
+// Min_25 sieve: returns f(1) + ... + f(n) for a multiplicative f with
+// f(p) = sum_k prime_poly[k] * p^k on primes and f(p^e) = prime_power(p, e).
+// The modulus must be a prime larger than prime_poly.len() + 1.
+pub fn min25_sieve<T, F>(n: u64, prime_poly: &[T], prime_power: F) -> T
+where
+    T: ModIntBase,
+    F: Fn(u64, u32) -> T,
+{
+    if n == 0 {
+        return T::raw(0);
+    }
+
+    let r = n.isqrt() as usize;
+    let mut small = vec![T::raw(0); r + 1];
+    let mut large = vec![T::raw(0); r + 1];
+    for (k, &coef) in prime_poly.iter().enumerate() {
+        let k = k as u64;
+        // Sums of i^k for i = 1..=j, j = 0..=k + 1, determine the degree k + 1
+        // polynomial giving the prefix sum of i^k.
+        let samples: Vec<T> = (0..=k + 1)
+            .scan(T::raw(0), |acc, j| {
+                if j > 0 {
+                    *acc += T::from(j).pow(k);
+                }
+                Some(*acc)
+            })
+            .collect();
+        let (table_small, table_large) = lucy_table(
+            n,
+            |p| T::from(p).pow(k),
+            |v| {
+                if v < 2 {
+                    T::raw(0)
+                } else {
+                    interpolate_consecutive(&samples, v) - T::raw(1)
+                }
+            },
+        );
+        for (s, &t) in small.iter_mut().zip(&table_small) {
+            *s += coef * t;
+        }
+        for (s, &t) in large.iter_mut().zip(&table_large) {
+            *s += coef * t;
+        }
+    }
+
+    let primes: Vec<u64> = Sieve::new(r).primes().iter().map(|&p| p as u64).collect();
+    let context = Min25 {
+        n,
+        r: r as u64,
+        small,
+        large,
+        primes,
+        prime_power,
+    };
+    context.sum(n, 0) + T::raw(1)
+}
+
+struct Min25<T, F> {
+    n: u64,
+    r: u64,
+    small: Vec<T>,
+    large: Vec<T>,
+    primes: Vec<u64>,
+    prime_power: F,
+}
+
+impl<T: ModIntBase, F: Fn(u64, u32) -> T> Min25<T, F> {
+    fn prime_sum(&self, v: u64) -> T {
+        if v <= self.r {
+            self.small[v as usize]
+        } else {
+            self.large[(self.n / v) as usize]
+        }
+    }
+
+    // Sum of f(i) over 2 <= i <= x whose smallest prime factor is at least
+    // primes[j].
+    fn sum(&self, x: u64, j: usize) -> T {
+        let below = if j == 0 {
+            T::raw(0)
+        } else {
+            self.prime_sum(self.primes[j - 1])
+        };
+        let mut result = self.prime_sum(x) - below;
+
+        for k in j..self.primes.len() {
+            let p = self.primes[k];
+            if p * p > x {
+                break;
+            }
+            let mut power = p;
+            let mut e = 1;
+            while power * p <= x {
+                result += (self.prime_power)(p, e) * self.sum(x / power, k + 1);
+                result += (self.prime_power)(p, e + 1);
+                power *= p;
+                e += 1;
+            }
+        }
+        result
+    }
+}
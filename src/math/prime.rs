@@ -1,37 +1,274 @@
+use super::gcd::gcd;
+use super::modint::{mul_mod, pow_mod};
+use super::sieve::Sieve;
+use std::ops::{Add, Mul, Sub};
+
+const SMALL_PRIMES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+// This witness set is known to be deterministic for every n < 2^64.
+const MILLER_RABIN_BASES: [u64; 7] = [2, 325, 9375, 28178, 450775, 9780504, 1795265022];
+
 pub fn is_prime(n: u64) -> bool {
     if n < 2 {
         return false;
     }
-    if n == 2 {
-        return true;
+    for &p in &SMALL_PRIMES {
+        if n == p {
+            return true;
+        }
+        if n.is_multiple_of(p) {
+            return false;
+        }
     }
-    if n.is_multiple_of(2) {
-        return false;
+    if n < 41 * 41 {
+        return true;
     }
 
-    let mut i = 3;
-    while i * i <= n {
-        if n.is_multiple_of(i) {
-            return false;
+    miller_rabin(n)
+}
+
+fn miller_rabin(n: u64) -> bool {
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+
+    'witness: for &base in &MILLER_RABIN_BASES {
+        let a = base % n;
+        if a == 0 {
+            continue;
         }
-        i += 2;
+
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'witness;
+            }
+        }
+        return false;
     }
     true
 }
 
 pub fn generate_primes(limit: u64) -> Vec<u64> {
-    (2..=limit).filter(|&n| is_prime(n)).collect()
+    Sieve::new(limit as usize)
+        .primes()
+        .iter()
+        .map(|&p| p as u64)
+        .collect()
 }
 
 pub fn nth_prime(n: usize) -> Option<u64> {
-    let mut count = 0;
-    let mut candidate = 1;
+    if n == 0 {
+        return None;
+    }
 
-    while count < n {
-        candidate += 1;
-        if is_prime(candidate) {
-            count += 1;
+    let sieve = Sieve::new(nth_prime_upper_bound(n));
+    sieve.primes().get(n - 1).map(|&p| p as u64)
+}
+
+// p_n < n (ln n + ln ln n) holds for every n >= 6.
+fn nth_prime_upper_bound(n: usize) -> usize {
+    if n < 6 {
+        return 11;
+    }
+    let x = n as f64;
+    (x * (x.ln() + x.ln().ln())) as usize + 1
+}
+
+pub fn factorize(n: u64) -> Vec<(u64, u32)> {
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let mut primes = Vec::new();
+    let mut n = n;
+    for &p in &SMALL_PRIMES {
+        while n.is_multiple_of(p) {
+            primes.push(p);
+            n /= p;
         }
     }
-    Some(candidate)
+    collect_prime_factors(n, &mut primes);
+    primes.sort_unstable();
+
+    let mut factors: Vec<(u64, u32)> = Vec::new();
+    for p in primes {
+        match factors.last_mut() {
+            Some((q, e)) if *q == p => *e += 1,
+            _ => factors.push((p, 1)),
+        }
+    }
+    factors
+}
+
+fn collect_prime_factors(n: u64, primes: &mut Vec<u64>) {
+    if n == 1 {
+        return;
+    }
+    if is_prime(n) {
+        primes.push(n);
+        return;
+    }
+
+    let d = pollard_rho(n);
+    collect_prime_factors(d, primes);
+    collect_prime_factors(n / d, primes);
+}
+
+// Returns a non-trivial divisor of the odd composite n, using Brent's
+// cycle detection and batching the gcd computations.
+fn pollard_rho(n: u64) -> u64 {
+    const BATCH: u64 = 128;
+    let diff = |a: u64, b: u64| a.abs_diff(b);
+
+    for c in 1.. {
+        let f = |x: u64| ((mul_mod(x, x, n) as u128 + c as u128) % n as u128) as u64;
+        let (mut x, mut y, mut ys) = (0, 2, 2);
+        let (mut g, mut q, mut r) = (1, 1, 1);
+
+        while g == 1 {
+            x = y;
+            for _ in 0..r {
+                y = f(y);
+            }
+            let mut k = 0;
+            while k < r && g == 1 {
+                ys = y;
+                for _ in 0..BATCH.min(r - k) {
+                    y = f(y);
+                    q = mul_mod(q, diff(x, y), n);
+                }
+                g = gcd(q, n);
+                k += BATCH;
+            }
+            r <<= 1;
+        }
+
+        if g == n {
+            loop {
+                ys = f(ys);
+                g = gcd(diff(x, ys), n);
+                if g > 1 {
+                    break;
+                }
+            }
+        }
+        if g != n {
+            return g;
+        }
+    }
+    unreachable!()
+}
+
+pub fn divisors(n: u64) -> Vec<u64> {
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let mut result = vec![1];
+    for (p, e) in factorize(n) {
+        let len = result.len();
+        let mut power = 1;
+        for _ in 0..e {
+            power *= p;
+            for i in 0..len {
+                result.push(result[i] * power);
+            }
+        }
+    }
+    result.sort_unstable();
+    result
+}
+
+pub fn euler_phi(n: u64) -> u64 {
+    factorize(n)
+        .into_iter()
+        .fold(n, |phi, (p, _)| phi / p * (p - 1))
+}
+
+pub fn primitive_root(p: u64) -> u64 {
+    assert!(is_prime(p), "Modulus must be prime");
+    if p == 2 {
+        return 1;
+    }
+
+    let factors = factorize(p - 1);
+    (2..)
+        .find(|&g| {
+            factors
+                .iter()
+                .all(|&(q, _)| pow_mod(g, (p - 1) / q, p) != 1)
+        })
+        .unwrap()
+}
+
+pub fn prime_count(n: u64) -> u64 {
+    prime_sum_by(n, |_| 1u64, |v| v.saturating_sub(1))
+}
+
+pub fn prime_sum(n: u64) -> u128 {
+    prime_sum_by(
+        n,
+        |p| p as u128,
+        |v| {
+            let v = v as u128;
+            (v * (v + 1) / 2).saturating_sub(1)
+        },
+    )
+}
+
+// Sums f(p) over primes p <= n in O(n^(3/4)). `f` must be completely
+// multiplicative and `prefix(v)` must return f(2) + f(3) + ... + f(v).
+pub fn prime_sum_by<T, F, P>(n: u64, f: F, prefix: P) -> T
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+    F: Fn(u64) -> T,
+    P: Fn(u64) -> T,
+{
+    let (small, large) = lucy_table(n, f, prefix);
+    if n as usize >= small.len() {
+        large[1]
+    } else {
+        small[n as usize]
+    }
+}
+
+// Returns `small[v]` for v <= sqrt(n) and `large[i]` for the values n / i,
+// each holding the sum of f(p) over primes p <= v.
+pub(crate) fn lucy_table<T, F, P>(n: u64, f: F, prefix: P) -> (Vec<T>, Vec<T>)
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+    F: Fn(u64) -> T,
+    P: Fn(u64) -> T,
+{
+    let r = n.isqrt() as usize;
+    let mut small: Vec<T> = (0..=r).map(|v| prefix(v as u64)).collect();
+    let mut large: Vec<T> = (0..=r)
+        .map(|i| prefix(if i == 0 { 0 } else { n / i as u64 }))
+        .collect();
+
+    for &p in Sieve::new(r).primes() {
+        let fp = f(p as u64);
+        let below = small[p - 1];
+        let p2 = (p * p) as u64;
+
+        let limit = r.min((n / p2) as usize);
+        for i in 1..=limit {
+            let d = i * p;
+            let quotient = if d <= r {
+                large[d]
+            } else {
+                small[(n / d as u64) as usize]
+            };
+            large[i] = large[i] - fp * (quotient - below);
+        }
+        for v in (p * p..=r).rev() {
+            small[v] = small[v] - fp * (small[v / p] - below);
+        }
+    }
+
+    (small, large)
 }
@@ -0,0 +1,291 @@
+use super::gcd::{Integer, SignedInteger};
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+pub trait RationalInteger: SignedInteger + Into<i128> + TryFrom<i128> {}
+
+impl<T: SignedInteger + Into<i128> + TryFrom<i128>> RationalInteger for T {}
+
+// Always stored in lowest terms with a positive denominator, so the derived
+// equality and hash agree with numeric equality.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational<T> {
+    num: T,
+    den: T,
+}
+
+impl<T: RationalInteger> Rational<T> {
+    pub fn new(num: T, den: T) -> Self {
+        Self::checked_new(num, den).expect("Rational overflowed")
+    }
+
+    pub fn checked_new(num: T, den: T) -> Option<Self> {
+        Self::from_i128(num.into(), den.into())
+    }
+
+    fn from_i128(num: i128, den: i128) -> Option<Self> {
+        assert!(den != 0, "Denominator must be nonzero");
+        // Dividing by a gcd of 2^127 wraps to i128::MIN, which flips both
+        // signs and so still preserves the ratio.
+        let g = num.unsigned_abs().gcd(den.unsigned_abs()) as i128;
+        let (mut num, mut den) = (num / g, den / g);
+        if den < 0 {
+            num = num.checked_neg()?;
+            den = den.checked_neg()?;
+        }
+        Some(Rational {
+            num: T::try_from(num).ok()?,
+            den: T::try_from(den).ok()?,
+        })
+    }
+
+    pub fn integer(value: T) -> Self {
+        Self::from_i128(value.into(), 1).unwrap()
+    }
+
+    pub fn zero() -> Self {
+        Self::from_i128(0, 1).unwrap()
+    }
+
+    pub fn one() -> Self {
+        Self::from_i128(1, 1).unwrap()
+    }
+
+    pub fn numer(&self) -> T {
+        self.num
+    }
+
+    pub fn denom(&self) -> T {
+        self.den
+    }
+
+    fn parts(&self) -> (i128, i128) {
+        (self.num.into(), self.den.into())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.parts().0 == 0
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.parts().1 == 1
+    }
+
+    pub fn floor(&self) -> T {
+        let (num, den) = self.parts();
+        T::try_from(num.div_euclid(den)).ok().unwrap()
+    }
+
+    pub fn ceil(&self) -> T {
+        let (num, den) = self.parts();
+        let floor = num.div_euclid(den);
+        let ceil = if num.rem_euclid(den) == 0 {
+            floor
+        } else {
+            floor + 1
+        };
+        T::try_from(ceil).ok().expect("Rational overflowed")
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let (num, den) = self.parts();
+        num as f64 / den as f64
+    }
+
+    pub fn abs(&self) -> Self {
+        self.checked_abs().expect("Rational overflowed")
+    }
+
+    pub fn checked_abs(&self) -> Option<Self> {
+        let (num, den) = self.parts();
+        Self::from_i128(num.checked_abs()?, den)
+    }
+
+    pub fn recip(&self) -> Self {
+        assert!(!self.is_zero(), "Reciprocal of zero");
+        self.checked_recip().expect("Rational overflowed")
+    }
+
+    pub fn checked_recip(&self) -> Option<Self> {
+        let (num, den) = self.parts();
+        if num == 0 {
+            return None;
+        }
+        Self::from_i128(den, num)
+    }
+
+    pub fn checked_neg(&self) -> Option<Self> {
+        let (num, den) = self.parts();
+        Self::from_i128(num.checked_neg()?, den)
+    }
+
+    pub fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        let (a, b) = self.parts();
+        let (c, d) = rhs.parts();
+        let g = b.gcd(d);
+        let num = a.checked_mul(d / g)?.checked_add(c.checked_mul(b / g)?)?;
+        Self::from_i128(num, (b / g).checked_mul(d)?)
+    }
+
+    pub fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        self.checked_add(&rhs.checked_neg()?)
+    }
+
+    pub fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        let (a, b) = self.parts();
+        let (c, d) = rhs.parts();
+        // Cross-cancel first so that products of reduced values stay small.
+        let g1 = a.gcd(d);
+        let g2 = c.gcd(b);
+        let num = (a / g1).checked_mul(c / g2)?;
+        let den = (b / g2).checked_mul(d / g1)?;
+        Self::from_i128(num, den)
+    }
+
+    // Returns None on overflow or division by zero.
+    pub fn checked_div(&self, rhs: &Self) -> Option<Self> {
+        self.checked_mul(&rhs.checked_recip()?)
+    }
+
+    pub fn pow(&self, exp: i32) -> Self {
+        if exp < 0 {
+            assert!(!self.is_zero(), "Reciprocal of zero");
+        }
+        self.checked_pow(exp).expect("Rational overflowed")
+    }
+
+    pub fn checked_pow(&self, exp: i32) -> Option<Self> {
+        let mut base = if exp < 0 {
+            self.checked_recip()?
+        } else {
+            *self
+        };
+        let mut exp = exp.unsigned_abs();
+        let mut result = Self::one();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.checked_mul(&base)?;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.checked_mul(&base)?;
+            }
+        }
+        Some(result)
+    }
+}
+
+// Compares a / b with c / d for positive b and d. Falls back to comparing
+// continued fraction expansions when the cross products overflow i128.
+fn cmp_fraction(a: i128, b: i128, c: i128, d: i128) -> Ordering {
+    if let (Some(x), Some(y)) = (a.checked_mul(d), c.checked_mul(b)) {
+        return x.cmp(&y);
+    }
+
+    let (qa, ra) = (a.div_euclid(b), a.rem_euclid(b));
+    let (qc, rc) = (c.div_euclid(d), c.rem_euclid(d));
+    if qa != qc {
+        return qa.cmp(&qc);
+    }
+    match (ra == 0, rc == 0) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (false, false) => cmp_fraction(d, rc, b, ra),
+    }
+}
+
+impl<T: RationalInteger> Ord for Rational<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a, b) = self.parts();
+        let (c, d) = other.parts();
+        cmp_fraction(a, b, c, d)
+    }
+}
+
+impl<T: RationalInteger> PartialOrd for Rational<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: RationalInteger> From<T> for Rational<T> {
+    fn from(value: T) -> Self {
+        Self::integer(value)
+    }
+}
+
+impl<T: RationalInteger> Neg for Rational<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.checked_neg().expect("Rational overflowed")
+    }
+}
+
+impl<T: RationalInteger> Div for Rational<T> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        assert!(!rhs.is_zero(), "Division by zero");
+        self.checked_div(&rhs).expect("Rational overflowed")
+    }
+}
+
+macro_rules! impl_rational_binop {
+    ($op:ident, $method:ident, $checked:ident, $op_assign:ident, $method_assign:ident) => {
+        impl<T: RationalInteger> $op for Rational<T> {
+            type Output = Self;
+
+            fn $method(self, rhs: Self) -> Self {
+                self.$checked(&rhs).expect("Rational overflowed")
+            }
+        }
+
+        impl<T: RationalInteger> $op_assign for Rational<T> {
+            fn $method_assign(&mut self, rhs: Self) {
+                *self = $op::$method(*self, rhs);
+            }
+        }
+    };
+}
+
+impl_rational_binop!(Add, add, checked_add, AddAssign, add_assign);
+impl_rational_binop!(Sub, sub, checked_sub, SubAssign, sub_assign);
+impl_rational_binop!(Mul, mul, checked_mul, MulAssign, mul_assign);
+
+impl<T: RationalInteger> DivAssign for Rational<T> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl<T: RationalInteger> Sum for Rational<T> {
+    fn sum<It: Iterator<Item = Self>>(iter: It) -> Self {
+        iter.fold(Self::zero(), |acc, x| acc + x)
+    }
+}
+
+impl<T: RationalInteger> Product for Rational<T> {
+    fn product<It: Iterator<Item = Self>>(iter: It) -> Self {
+        iter.fold(Self::one(), |acc, x| acc * x)
+    }
+}
+
+impl<T: RationalInteger + fmt::Display> fmt::Display for Rational<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+impl<T: RationalInteger + fmt::Display> fmt::Debug for Rational<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
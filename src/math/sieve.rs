@@ -0,0 +1,229 @@
+use super::prime::is_prime;
+
+pub struct Sieve {
+    limit: usize,
+    smallest_factor: Vec<usize>,
+    primes: Vec<usize>,
+}
+
+impl Sieve {
+    pub fn new(limit: usize) -> Self {
+        let mut smallest_factor = vec![0; limit + 1];
+        let mut primes = Vec::new();
+
+        for i in 2..=limit {
+            if smallest_factor[i] == 0 {
+                smallest_factor[i] = i;
+                primes.push(i);
+            }
+            for &p in &primes {
+                if p > smallest_factor[i] || i * p > limit {
+                    break;
+                }
+                smallest_factor[i * p] = p;
+            }
+        }
+
+        Sieve {
+            limit,
+            smallest_factor,
+            primes,
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    pub fn primes(&self) -> &[usize] {
+        &self.primes
+    }
+
+    pub fn is_prime(&self, x: usize) -> bool {
+        assert!(x <= self.limit, "Value exceeds sieve limit");
+        x >= 2 && self.smallest_factor[x] == x
+    }
+
+    pub fn smallest_prime_factor(&self, x: usize) -> usize {
+        assert!(x <= self.limit, "Value exceeds sieve limit");
+        self.smallest_factor[x]
+    }
+
+    pub fn factorize(&self, x: usize) -> Vec<(usize, u32)> {
+        assert!(x <= self.limit, "Value exceeds sieve limit");
+        let mut factors: Vec<(usize, u32)> = Vec::new();
+        let mut x = x;
+
+        while x > 1 {
+            let p = self.smallest_factor[x];
+            let mut e = 0;
+            while x.is_multiple_of(p) {
+                x /= p;
+                e += 1;
+            }
+            factors.push((p, e));
+        }
+        factors
+    }
+
+    pub fn mobius(&self) -> Vec<i32> {
+        let mut mu = vec![0; self.limit + 1];
+        if self.limit >= 1 {
+            mu[1] = 1;
+        }
+
+        for i in 2..=self.limit {
+            let p = self.smallest_factor[i];
+            let rest = i / p;
+            mu[i] = if rest.is_multiple_of(p) { 0 } else { -mu[rest] };
+        }
+        mu
+    }
+
+    pub fn phi(&self) -> Vec<usize> {
+        let mut phi = vec![0; self.limit + 1];
+        if self.limit >= 1 {
+            phi[1] = 1;
+        }
+
+        for i in 2..=self.limit {
+            let p = self.smallest_factor[i];
+            let rest = i / p;
+            phi[i] = if rest.is_multiple_of(p) {
+                phi[rest] * p
+            } else {
+                phi[rest] * (p - 1)
+            };
+        }
+        phi
+    }
+}
+
+const SEGMENT_SIZE: u64 = 1 << 16;
+const MAX_SIEVE_ROOT: u64 = 1 << 24;
+
+pub struct SegmentedPrimes {
+    // Odd-only bitset up to sqrt(high); bit i is set when 2i + 1 is composite.
+    base_composite: Vec<u64>,
+    // Testing each candidate with Miller-Rabin beats sieving once every
+    // segment has to walk millions of base primes, or when the range is much
+    // narrower than sqrt(high).
+    test_individually: bool,
+    next_low: u64,
+    high: u64,
+    finished: bool,
+    segment: Vec<u64>,
+    position: usize,
+}
+
+fn odd_composite_bits(limit: u64) -> Vec<u64> {
+    let bits = (limit / 2 + 1) as usize;
+    let mut composite = vec![0u64; bits.div_ceil(64)];
+    composite[0] |= 1;
+
+    let mut i = 1;
+    while (2 * i + 1) * (2 * i + 1) <= limit as usize {
+        if composite[i / 64] >> (i % 64) & 1 == 0 {
+            let p = 2 * i + 1;
+            let mut j = p * p / 2;
+            while j < bits {
+                composite[j / 64] |= 1 << (j % 64);
+                j += p;
+            }
+        }
+        i += 1;
+    }
+    composite
+}
+
+impl SegmentedPrimes {
+    pub fn new(low: u64, high: u64) -> Self {
+        let root = high.isqrt();
+        let test_individually = root > MAX_SIEVE_ROOT || high.saturating_sub(low) < root >> 8;
+        let base_composite = if test_individually {
+            Vec::new()
+        } else {
+            odd_composite_bits(root)
+        };
+
+        SegmentedPrimes {
+            base_composite,
+            test_individually,
+            next_low: low.max(2),
+            high,
+            finished: low.max(2) > high,
+            segment: Vec::new(),
+            position: 0,
+        }
+    }
+
+    fn sieve_next_segment(&mut self) {
+        let low = self.next_low;
+        let high = self.high.min(low.saturating_add(SEGMENT_SIZE - 1));
+        self.segment.clear();
+        self.position = 0;
+
+        if self.test_individually {
+            self.segment.extend((low..=high).filter(|&x| is_prime(x)));
+        } else {
+            let mut composite = vec![false; (high - low + 1) as usize];
+            let mut mark = |p: u64| {
+                let first = match low.div_ceil(p).checked_mul(p) {
+                    Some(first) if first <= high => first,
+                    _ => return,
+                };
+                let mut j = (first.max(p * p) - low) as usize;
+                while j < composite.len() {
+                    composite[j] = true;
+                    j += p as usize;
+                }
+            };
+
+            let root = high.isqrt();
+            mark(2);
+            'outer: for (w, &word) in self.base_composite.iter().enumerate() {
+                let mut primes = !word;
+                while primes != 0 {
+                    let p = 2 * (w as u64 * 64 + primes.trailing_zeros() as u64) + 1;
+                    if p > root {
+                        break 'outer;
+                    }
+                    mark(p);
+                    primes &= primes - 1;
+                }
+            }
+
+            for (i, &is_composite) in composite.iter().enumerate() {
+                if !is_composite {
+                    self.segment.push(low + i as u64);
+                }
+            }
+        }
+
+        if high == self.high {
+            self.finished = true;
+        } else {
+            self.next_low = high + 1;
+        }
+    }
+}
+
+impl Iterator for SegmentedPrimes {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        while self.position == self.segment.len() {
+            if self.finished {
+                return None;
+            }
+            self.sieve_next_segment();
+        }
+
+        self.position += 1;
+        Some(self.segment[self.position - 1])
+    }
+}
+
+pub fn primes_in_range(low: u64, high: u64) -> Vec<u64> {
+    SegmentedPrimes::new(low, high).collect()
+}
@@ -1,4 +1,16 @@
-use ac_lib::math::{gcd, generate_primes, is_prime, lcm, nth_prime, ModInt};
+use ac_lib::math::{
+    berlekamp_massey, binom_mod_any, binom_mod_prime, binom_mod_prime_power, bostan_mori,
+    checked_lcm, convolution, convolution_any_mod, convolution_ll, crt, discrete_log, divisors,
+    euler_phi, ext_gcd, factorize, floor_sum, garner, garner_u128, gcd, generate_primes,
+    interpolate, interpolate_consecutive, is_prime, kth_root_mod, kth_term_of_linear_recurrence,
+    lcm, min25_sieve, mobius_prefix_sum, mul_mod, multipoint_eval, nth_prime, phi_prefix_sum,
+    pow_mod, prime_count, prime_sum, prime_sum_by, primes_in_range, primitive_root,
+    quotient_ranges, quotient_ranges_from, sqrt_mod, universal_euclid, BigInt, BitMatrix,
+    Combination, DynamicModInt, FormalPowerSeries, LucasCombination, Matrix, MaxPlus, MinPlus,
+    ModInt, ModInt1000000007, ModInt998244353, ModIntBase, Monoid, Rational, SegmentedPrimes,
+    Sieve, StaticModInt, XorBasis,
+};
+use ac_lib::modint_id;
 
 #[test]
 fn test_gcd() {
@@ -182,3 +194,2004 @@ fn test_modint_chain_operations() {
     let result = a.add(&b).mul(&c);
     assert_eq!(result.value(), 6);
 }
+
+#[test]
+fn test_static_modint_new() {
+    type Mint = StaticModInt<7>;
+    assert_eq!(Mint::new(5).value(), 5);
+    assert_eq!(Mint::new(-3).value(), 4);
+    assert_eq!(Mint::new(10).value(), 3);
+    assert_eq!(Mint::modulus(), 7);
+}
+
+#[test]
+fn test_static_modint_operators() {
+    type Mint = StaticModInt<7>;
+    let a = Mint::new(5);
+    let b = Mint::new(3);
+
+    assert_eq!((a + b).value(), 1);
+    assert_eq!((a - b).value(), 2);
+    assert_eq!((b - a).value(), 5);
+    assert_eq!((a * b).value(), 1);
+    assert_eq!((a / b).value(), 4);
+    assert_eq!((-a).value(), 2);
+}
+
+#[test]
+fn test_static_modint_assign_operators() {
+    let mut a = ModInt1000000007::new(10);
+    a += ModInt1000000007::new(5);
+    assert_eq!(a.value(), 15);
+    a -= ModInt1000000007::new(20);
+    assert_eq!(a.value(), 1000000002);
+    a *= ModInt1000000007::new(2);
+    assert_eq!(a.value(), 999999997);
+    a /= ModInt1000000007::new(2);
+    assert_eq!(a.value(), 1000000002);
+}
+
+#[test]
+fn test_static_modint_pow_inv() {
+    let a = ModInt998244353::new(3);
+    assert_eq!(a.pow(0).value(), 1);
+    assert_eq!(a.pow(10).value(), 59049);
+    assert_eq!((a * a.inv()).value(), 1);
+
+    type Mint = StaticModInt<10>;
+    assert_eq!(Mint::new(3).inv().value(), 7);
+}
+
+#[test]
+#[should_panic(expected = "Value is not invertible")]
+fn test_static_modint_inv_not_invertible() {
+    type Mint = StaticModInt<10>;
+    let _ = Mint::new(4).inv();
+}
+
+#[test]
+fn test_static_modint_large_modulus() {
+    type Mint = StaticModInt<4294967291>;
+    let a = Mint::new(4294967290);
+    assert_eq!((a + a).value(), 4294967289);
+    assert_eq!((a * a).value(), 1);
+}
+
+#[test]
+fn test_static_modint_sum_product() {
+    let values: Vec<ModInt1000000007> = (1..=10u64).map(ModInt1000000007::from).collect();
+    let sum: ModInt1000000007 = values.iter().sum();
+    let product: ModInt1000000007 = values.into_iter().product();
+    assert_eq!(sum.value(), 55);
+    assert_eq!(product.value(), 3628800);
+}
+
+#[test]
+fn test_static_modint_from_and_display() {
+    assert_eq!(ModInt1000000007::from(-1i64).value(), 1000000006);
+    assert_eq!(ModInt1000000007::from(1000000008u64).value(), 1);
+    assert_eq!(ModInt1000000007::from(7usize).value(), 7);
+    assert_eq!(ModInt998244353::new(42).to_string(), "42");
+    assert_eq!(format!("{:?}", ModInt998244353::new(42)), "42");
+}
+
+#[test]
+fn test_static_modint_from_str() {
+    let a: ModInt1000000007 = "1000000008".parse().unwrap();
+    assert_eq!(a.value(), 1);
+    let b: ModInt1000000007 = "-1".parse().unwrap();
+    assert_eq!(b.value(), 1000000006);
+    let c: ModInt1000000007 = "100000000000000000000000000000".parse().unwrap();
+    assert_eq!(c, ModInt1000000007::new(10).pow(29));
+    assert!("12a".parse::<ModInt1000000007>().is_err());
+    assert!("".parse::<ModInt1000000007>().is_err());
+}
+
+#[test]
+fn test_modint_inv() {
+    let a = ModInt::new(3, 7);
+    assert_eq!(a.inv().value(), 5);
+    assert_eq!(a.inv_prime().value(), 5);
+
+    let b = ModInt::new(3, 10);
+    assert_eq!(b.inv().value(), 7);
+    assert_eq!(ModInt::new(4, 10).checked_inv().map(|x| x.value()), None);
+}
+
+#[test]
+#[should_panic(expected = "Value is not invertible")]
+fn test_modint_inv_not_invertible() {
+    let _ = ModInt::new(6, 9).inv();
+}
+
+#[test]
+fn test_modint_div() {
+    let a = ModInt::new(6, 1000000007);
+    let b = ModInt::new(4, 1000000007);
+    let c = a.div(&b);
+    assert_eq!(c.mul(&b).value(), 6);
+
+    let d = ModInt::new(9, 10).div(&ModInt::new(7, 10));
+    assert_eq!(d.value(), 7);
+}
+
+#[test]
+fn test_modint_mersenne61() {
+    let p = (1i64 << 61) - 1;
+    let a = ModInt::new(p - 1, p);
+    assert_eq!(a.mul(&a).value(), 1);
+    assert_eq!(a.add(&a).value(), p - 2);
+
+    let b = ModInt::new(123456789123456789, p);
+    assert_eq!(b.pow(p - 1).value(), 1);
+    assert_eq!(b.mul(&b.inv()).value(), 1);
+    assert_eq!(b.inv_prime().value(), b.inv().value());
+}
+
+#[test]
+fn test_modint_max_modulus() {
+    let m = i64::MAX;
+    let a = ModInt::new(m - 1, m);
+    assert_eq!(a.add(&a).value(), m - 2);
+    assert_eq!(a.mul(&a).value(), 1);
+    assert_eq!(ModInt::new(i64::MIN, m).value(), m - 1);
+    assert_eq!(ModInt::new(-1, m).sub(&a).value(), 0);
+}
+
+#[test]
+fn test_dynamic_modint_operators() {
+    modint_id!(OperatorsId);
+    type Mint = DynamicModInt<OperatorsId>;
+    Mint::set_modulus(7);
+
+    let a = Mint::new(5);
+    let b = Mint::new(3);
+    assert_eq!(Mint::modulus(), 7);
+    assert_eq!((a + b).value(), 1);
+    assert_eq!((a - b).value(), 2);
+    assert_eq!((a * b).value(), 1);
+    assert_eq!((a / b).value(), 4);
+    assert_eq!((-a).value(), 2);
+    assert_eq!(a.pow(6).value(), 1);
+
+    let mut c = a;
+    c *= b;
+    c += a;
+    assert_eq!(c.value(), 6);
+}
+
+#[test]
+fn test_dynamic_modint_barrett_matches_naive() {
+    modint_id!(BarrettId);
+    type Mint = DynamicModInt<BarrettId>;
+
+    for &m in &[
+        1u32,
+        2,
+        998244353,
+        1000000007,
+        2147483647,
+        4294967291,
+        u32::MAX,
+    ] {
+        Mint::set_modulus(m);
+        let mut x = 12345u64;
+        for _ in 0..1000 {
+            x = x
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let a = (x >> 32) % m as u64;
+            let b = (x & 0xffff_ffff) % m as u64;
+            let product = Mint::from(a) * Mint::from(b);
+            assert_eq!(product.value() as u64, a * b % m as u64);
+        }
+    }
+}
+
+#[test]
+fn test_dynamic_modint_ids_are_independent() {
+    modint_id!(FirstId);
+    modint_id!(SecondId);
+    DynamicModInt::<FirstId>::set_modulus(11);
+    DynamicModInt::<SecondId>::set_modulus(13);
+
+    assert_eq!(DynamicModInt::<FirstId>::new(20).value(), 9);
+    assert_eq!(DynamicModInt::<SecondId>::new(20).value(), 7);
+}
+
+#[test]
+fn test_dynamic_modint_inv_non_prime() {
+    modint_id!(NonPrimeId);
+    type Mint = DynamicModInt<NonPrimeId>;
+    Mint::set_modulus(10);
+    assert_eq!(Mint::new(3).inv().value(), 7);
+    let parsed: Mint = "123".parse().unwrap();
+    assert_eq!(parsed.value(), 3);
+}
+
+fn sum_of_squares<T: ModIntBase>(n: u64) -> T {
+    (1..=n).map(|i| T::from(i) * T::from(i)).sum()
+}
+
+#[test]
+fn test_modint_base_generic() {
+    modint_id!(GenericId);
+    DynamicModInt::<GenericId>::set_modulus(1000000007);
+    assert_eq!(sum_of_squares::<ModInt1000000007>(100).value(), 338350);
+    assert_eq!(
+        sum_of_squares::<DynamicModInt<GenericId>>(100).value(),
+        338350
+    );
+}
+
+#[test]
+fn test_is_prime_miller_rabin() {
+    assert!(is_prime(1_000_000_007));
+    assert!(is_prime(998_244_353));
+    assert!(is_prime((1 << 61) - 1));
+    assert!(is_prime(18_446_744_073_709_551_557));
+    assert!(!is_prime(u64::MAX));
+    assert!(!is_prime(1_000_000_007 * 998_244_353));
+    assert!(!is_prime(4_294_967_291 * 4_294_967_291));
+    // Strong pseudoprimes to several small bases.
+    assert!(!is_prime(3_215_031_751));
+    assert!(!is_prime(3_825_123_056_546_413_051));
+}
+
+#[test]
+fn test_is_prime_matches_trial_division() {
+    fn naive(n: u64) -> bool {
+        n >= 2
+            && (2..)
+                .take_while(|i| i * i <= n)
+                .all(|i| !n.is_multiple_of(i))
+    }
+    for n in 0..20000 {
+        assert_eq!(is_prime(n), naive(n), "n = {}", n);
+    }
+}
+
+#[test]
+fn test_factorize() {
+    assert_eq!(factorize(1), vec![]);
+    assert_eq!(factorize(2), vec![(2, 1)]);
+    assert_eq!(factorize(360), vec![(2, 3), (3, 2), (5, 1)]);
+    assert_eq!(factorize(1_000_000_007), vec![(1_000_000_007, 1)]);
+    assert_eq!(
+        factorize(998_244_353 * 1_000_000_007),
+        vec![(998_244_353, 1), (1_000_000_007, 1)]
+    );
+    assert_eq!(
+        factorize(4_294_967_291 * 4_294_967_291),
+        vec![(4_294_967_291, 2)]
+    );
+    assert_eq!(
+        factorize(u64::MAX),
+        vec![
+            (3, 1),
+            (5, 1),
+            (17, 1),
+            (257, 1),
+            (641, 1),
+            (65537, 1),
+            (6700417, 1)
+        ]
+    );
+}
+
+#[test]
+fn test_factorize_matches_product() {
+    let mut x = 88172645463325252u64;
+    for _ in 0..200 {
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        let n = x | 1;
+        let factors = factorize(n);
+        let product = factors.iter().fold(1u64, |acc, &(p, e)| acc * p.pow(e));
+        assert_eq!(product, n);
+        assert!(factors.iter().all(|&(p, _)| is_prime(p)));
+    }
+}
+
+#[test]
+fn test_divisors() {
+    assert_eq!(divisors(1), vec![1]);
+    assert_eq!(divisors(12), vec![1, 2, 3, 4, 6, 12]);
+    assert_eq!(divisors(49), vec![1, 7, 49]);
+    assert_eq!(divisors(720720).len(), 240);
+}
+
+#[test]
+fn test_euler_phi() {
+    assert_eq!(euler_phi(1), 1);
+    assert_eq!(euler_phi(9), 6);
+    assert_eq!(euler_phi(36), 12);
+    assert_eq!(euler_phi(1_000_000_007), 1_000_000_006);
+}
+
+#[test]
+fn test_primitive_root() {
+    assert_eq!(primitive_root(2), 1);
+    assert_eq!(primitive_root(7), 3);
+    assert_eq!(primitive_root(998_244_353), 3);
+    assert_eq!(primitive_root(1_000_000_007), 5);
+}
+
+#[test]
+fn test_sieve_primes() {
+    let sieve = Sieve::new(30);
+    assert_eq!(sieve.primes(), &[2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+    assert!(!sieve.is_prime(0));
+    assert!(!sieve.is_prime(1));
+    assert!(sieve.is_prime(29));
+    assert!(!sieve.is_prime(27));
+    assert_eq!(sieve.smallest_prime_factor(21), 3);
+    assert_eq!(Sieve::new(1).primes(), &[] as &[usize]);
+}
+
+#[test]
+fn test_sieve_matches_is_prime() {
+    let sieve = Sieve::new(100000);
+    for x in 0..=100000 {
+        assert_eq!(sieve.is_prime(x), is_prime(x as u64));
+    }
+    assert_eq!(sieve.primes().len(), 9592);
+}
+
+#[test]
+fn test_sieve_factorize() {
+    let sieve = Sieve::new(1000);
+    assert_eq!(sieve.factorize(1), vec![]);
+    assert_eq!(sieve.factorize(360), vec![(2, 3), (3, 2), (5, 1)]);
+    assert_eq!(sieve.factorize(997), vec![(997, 1)]);
+    for x in 1..=1000 {
+        let expected: Vec<(usize, u32)> = factorize(x as u64)
+            .into_iter()
+            .map(|(p, e)| (p as usize, e))
+            .collect();
+        assert_eq!(sieve.factorize(x), expected);
+    }
+}
+
+#[test]
+fn test_sieve_mobius_phi() {
+    let sieve = Sieve::new(12);
+    assert_eq!(
+        sieve.mobius(),
+        vec![0, 1, -1, -1, 0, -1, 1, -1, 0, 0, 1, -1, 0]
+    );
+    assert_eq!(sieve.phi(), vec![0, 1, 1, 2, 2, 4, 2, 6, 4, 6, 4, 10, 4]);
+
+    let sieve = Sieve::new(1000);
+    let phi = sieve.phi();
+    for (x, &value) in phi.iter().enumerate().skip(1) {
+        assert_eq!(value as u64, euler_phi(x as u64));
+    }
+}
+
+#[test]
+#[should_panic(expected = "Value exceeds sieve limit")]
+fn test_sieve_out_of_range() {
+    let sieve = Sieve::new(10);
+    let _ = sieve.is_prime(11);
+}
+
+#[test]
+fn test_nth_prime_large() {
+    assert_eq!(nth_prime(0), None);
+    assert_eq!(nth_prime(5), Some(11));
+    assert_eq!(nth_prime(6), Some(13));
+    assert_eq!(nth_prime(10000), Some(104729));
+    assert_eq!(nth_prime(1000000), Some(15485863));
+}
+
+#[test]
+fn test_primes_in_range() {
+    assert_eq!(primes_in_range(0, 20), vec![2, 3, 5, 7, 11, 13, 17, 19]);
+    assert_eq!(primes_in_range(14, 16), Vec::<u64>::new());
+    assert_eq!(primes_in_range(20, 10), Vec::<u64>::new());
+    assert_eq!(primes_in_range(2, 2), vec![2]);
+    assert_eq!(primes_in_range(0, 1000000), generate_primes(1000000));
+}
+
+#[test]
+fn test_primes_in_range_large() {
+    let low = 1_000_000_000_000;
+    let primes = primes_in_range(low, low + 1000);
+    assert!(primes.iter().all(|&p| is_prime(p)));
+    let expected = (low..=low + 1000).filter(|&x| is_prime(x)).count();
+    assert_eq!(primes.len(), expected);
+    assert_eq!(primes[0], 1_000_000_000_039);
+}
+
+#[test]
+fn test_primes_in_range_near_u64_max() {
+    let primes = primes_in_range(u64::MAX - 100, u64::MAX);
+    assert_eq!(primes, vec![u64::MAX - 94, u64::MAX - 82, u64::MAX - 58]);
+    assert_eq!(
+        primes_in_range(1_000_000_000_000_000_000, 1_000_000_000_000_000_010),
+        vec![1_000_000_000_000_000_003, 1_000_000_000_000_000_009]
+    );
+}
+
+#[test]
+fn test_segmented_primes_streaming() {
+    let low = 1_000_000_000;
+    let count = SegmentedPrimes::new(low, low + 300_000).count();
+    let expected = (low..=low + 300_000).filter(|&x| is_prime(x)).count();
+    assert_eq!(count, expected);
+
+    let first: Vec<u64> = SegmentedPrimes::new(100, u64::MAX >> 24).take(3).collect();
+    assert_eq!(first, vec![101, 103, 107]);
+}
+
+#[test]
+fn test_prime_count() {
+    assert_eq!(prime_count(0), 0);
+    assert_eq!(prime_count(1), 0);
+    assert_eq!(prime_count(2), 1);
+    assert_eq!(prime_count(3), 2);
+    assert_eq!(prime_count(100), 25);
+    assert_eq!(prime_count(1_000_000), 78498);
+    assert_eq!(prime_count(1_000_000_000), 50847534);
+    for n in 0..2000u64 {
+        assert_eq!(prime_count(n), generate_primes(n).len() as u64);
+    }
+}
+
+#[test]
+fn test_prime_sum() {
+    assert_eq!(prime_sum(10), 17);
+    assert_eq!(prime_sum(1_000_000), 37550402023);
+    assert_eq!(prime_sum(1_000_000_000), 24739512092254535);
+}
+
+#[test]
+fn test_prime_sum_by_modint() {
+    let expected: u64 = generate_primes(100000)
+        .iter()
+        .map(|&p| p * p % 998244353)
+        .sum();
+    let sum = prime_sum_by(
+        100000,
+        |p| ModInt998244353::from(p * p),
+        |v| {
+            let v = ModInt998244353::from(v);
+            v * (v + ModInt998244353::new(1))
+                * (v * ModInt998244353::new(2) + ModInt998244353::new(1))
+                / ModInt998244353::new(6)
+                - ModInt998244353::new(1)
+        },
+    );
+    assert_eq!(sum, ModInt998244353::from(expected));
+}
+
+#[test]
+fn test_gcd_generic() {
+    assert_eq!(gcd(48u8, 18u8), 6);
+    assert_eq!(gcd(-48i32, 18i32), 6);
+    assert_eq!(gcd(48i64, -18i64), 6);
+    assert_eq!(gcd(0u128, 0u128), 0);
+    assert_eq!(gcd(1u64 << 40, 3u64 << 20), 1 << 20);
+    assert_eq!(gcd(u64::MAX, u64::MAX - 1), 1);
+    assert_eq!(
+        gcd(1_000_000_007usize * 6, 1_000_000_007usize * 4),
+        2_000_000_014
+    );
+}
+
+#[test]
+fn test_gcd_signed_min() {
+    assert_eq!(gcd(i64::MIN, 6), 2);
+    assert_eq!(gcd(i64::MIN, i64::MAX), 1);
+    assert_eq!(gcd(i64::MIN.unsigned_abs(), 0), 1 << 63);
+}
+
+#[test]
+#[should_panic(expected = "gcd overflowed")]
+fn test_gcd_signed_min_overflow() {
+    gcd(i64::MIN, 0);
+}
+
+#[test]
+fn test_ext_gcd_signed_min() {
+    assert_eq!(ext_gcd(i64::MIN, -1), (1, 0, -1));
+    assert_eq!(ext_gcd(-1, i64::MIN), (1, -1, 0));
+    assert_eq!(ext_gcd(i64::MIN, 1), (1, 0, 1));
+    assert_eq!(ext_gcd(i8::MIN, -1i8), (1, 0, -1));
+    for &b in &[3i64, -3, 6, i64::MAX, i64::MIN + 1] {
+        let (g, x, y) = ext_gcd(i64::MIN, b);
+        assert_eq!(g, gcd(i64::MIN, b));
+        assert_eq!(
+            i64::MIN as i128 * x as i128 + b as i128 * y as i128,
+            g as i128
+        );
+    }
+}
+
+#[test]
+#[should_panic(expected = "gcd overflowed")]
+fn test_ext_gcd_signed_min_overflow() {
+    ext_gcd(i64::MIN, 0);
+}
+
+#[test]
+fn test_lcm_generic() {
+    assert_eq!(lcm(0u32, 5u32), 0);
+    assert_eq!(lcm(-4i64, 6i64), 12);
+    assert_eq!(lcm(1u128 << 100, 3u128), 3 << 100);
+}
+
+#[test]
+fn test_checked_lcm() {
+    assert_eq!(checked_lcm(4u64, 6u64), Some(12));
+    assert_eq!(checked_lcm(u64::MAX, u64::MAX - 1), None);
+    assert_eq!(checked_lcm(200u8, 3u8), None);
+    assert_eq!(checked_lcm(i64::MIN, 1), None);
+    assert_eq!(checked_lcm(-3i8, 5i8), Some(15));
+}
+
+#[test]
+#[should_panic(expected = "lcm overflowed")]
+fn test_lcm_overflow_panics() {
+    let _ = lcm(u32::MAX, u32::MAX - 1);
+}
+
+#[test]
+fn test_ext_gcd() {
+    for &(a, b) in &[
+        (240i64, 46i64),
+        (-240, 46),
+        (240, -46),
+        (0, 5),
+        (5, 0),
+        (17, 17),
+    ] {
+        let (g, x, y) = ext_gcd(a, b);
+        assert_eq!(g, gcd(a, b));
+        assert_eq!(a * x + b * y, g);
+    }
+    assert_eq!(ext_gcd(0i32, 0i32), (0, 1, 0));
+
+    let (g, x, y) = ext_gcd(i128::from(i64::MAX), 1_000_000_007i128);
+    assert_eq!(g, 1);
+    assert_eq!(i128::from(i64::MAX) * x + 1_000_000_007 * y, 1);
+}
+
+#[test]
+fn test_crt() {
+    assert_eq!(crt(&[]), Some((0, 1)));
+    assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+    assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+    assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+    assert_eq!(crt(&[(-1, 5), (4, 10)]), Some((4, 10)));
+    assert_eq!(crt(&[(3, 12), (1, 4)]), None);
+
+    let (x, m) = crt(&[(1, 1_000_000_007), (2, 998_244_353)]).unwrap();
+    assert_eq!(m, 1_000_000_007 * 998_244_353);
+    assert_eq!(x % 1_000_000_007, 1);
+    assert_eq!(x % 998_244_353, 2);
+}
+
+#[test]
+fn test_modint_modulus() {
+    assert_eq!(ModInt::new(3, 7).modulus(), 7);
+}
+
+#[test]
+fn test_garner() {
+    let residues = [ModInt::new(2, 3), ModInt::new(3, 5), ModInt::new(2, 7)];
+    assert_eq!(garner(&residues, 1000), 23);
+    assert_eq!(garner(&residues, 10), 3);
+    assert_eq!(garner_u128(&residues), Some(23));
+    assert_eq!(garner(&[], 1000), 0);
+    assert_eq!(garner_u128(&[]), Some(0));
+}
+
+#[test]
+fn test_garner_large_value() {
+    let x: u128 = 123_456_789_012_345_678_901_234_567;
+    let moduli = [998_244_353i64, 1_000_000_007, 1_000_000_009, 754_974_721];
+    let residues: Vec<ModInt> = moduli
+        .iter()
+        .map(|&m| ModInt::new((x % m as u128) as i64, m))
+        .collect();
+
+    assert_eq!(garner_u128(&residues), Some(x));
+    assert_eq!(garner(&residues, 1_000_000_007), (x % 1_000_000_007) as i64);
+    assert_eq!(
+        garner(&residues, (1 << 61) - 1),
+        (x % ((1 << 61) - 1)) as i64
+    );
+}
+
+#[test]
+fn test_garner_u128_overflow() {
+    let p = (1i64 << 61) - 1;
+    let residues = [
+        ModInt::new(p - 1, p),
+        ModInt::new(1_000_000_006, 1_000_000_007),
+        ModInt::new(998_244_352, 998_244_353),
+        ModInt::new(1_000_000_008, 1_000_000_009),
+    ];
+    assert_eq!(garner_u128(&residues), None);
+}
+
+#[test]
+#[should_panic(expected = "Moduli must be pairwise coprime")]
+fn test_garner_not_coprime() {
+    let _ = garner(&[ModInt::new(1, 4), ModInt::new(3, 6)], 100);
+}
+
+fn xorshift(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+fn naive_convolution<T: ModIntBase>(a: &[T], b: &[T]) -> Vec<T> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut result = vec![T::raw(0); a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            result[i + j] += x * y;
+        }
+    }
+    result
+}
+
+#[test]
+fn test_convolution_small() {
+    let a: Vec<ModInt998244353> = [1, 2, 3].iter().map(|&x| ModInt998244353::new(x)).collect();
+    let b: Vec<ModInt998244353> = [4, 5].iter().map(|&x| ModInt998244353::new(x)).collect();
+    let c: Vec<u32> = convolution(&a, &b).iter().map(|x| x.value()).collect();
+    assert_eq!(c, vec![4, 13, 22, 15]);
+    assert!(convolution(&a, &[]).is_empty());
+}
+
+#[test]
+fn test_convolution_matches_naive() {
+    let mut state = 2463534242;
+    for &(n, m) in &[(1, 1), (61, 61), (100, 200), (255, 257), (1000, 1)] {
+        let a: Vec<ModInt998244353> = (0..n)
+            .map(|_| ModInt998244353::from(xorshift(&mut state)))
+            .collect();
+        let b: Vec<ModInt998244353> = (0..m)
+            .map(|_| ModInt998244353::from(xorshift(&mut state)))
+            .collect();
+        assert_eq!(convolution(&a, &b), naive_convolution(&a, &b));
+    }
+}
+
+#[test]
+fn test_convolution_other_ntt_prime() {
+    type Mint = StaticModInt<469762049>;
+    let mut state = 88172645463325252;
+    let a: Vec<Mint> = (0..300).map(|_| Mint::from(xorshift(&mut state))).collect();
+    let b: Vec<Mint> = (0..150).map(|_| Mint::from(xorshift(&mut state))).collect();
+    assert_eq!(convolution(&a, &b), naive_convolution(&a, &b));
+}
+
+#[test]
+fn test_convolution_ll() {
+    assert_eq!(convolution_ll(&[1, -2, 3], &[-4, 5]), vec![-4, 13, -22, 15]);
+    assert!(convolution_ll(&[], &[1]).is_empty());
+
+    let mut state = 1234567;
+    let a: Vec<i64> = (0..200)
+        .map(|_| (xorshift(&mut state) % 200_000_001) as i64 - 100_000_000)
+        .collect();
+    let b: Vec<i64> = (0..300)
+        .map(|_| (xorshift(&mut state) % 200_000_001) as i64 - 100_000_000)
+        .collect();
+    let mut expected = vec![0i64; a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            expected[i + j] += x * y;
+        }
+    }
+    assert_eq!(convolution_ll(&a, &b), expected);
+}
+
+#[test]
+fn test_convolution_any_mod() {
+    let mut state = 362436069;
+    for &(n, m) in &[(3, 2), (61, 100), (500, 300), (1, 1000)] {
+        let a: Vec<ModInt1000000007> = (0..n)
+            .map(|_| ModInt1000000007::from(xorshift(&mut state)))
+            .collect();
+        let b: Vec<ModInt1000000007> = (0..m)
+            .map(|_| ModInt1000000007::from(xorshift(&mut state)))
+            .collect();
+        assert_eq!(convolution_any_mod(&a, &b), naive_convolution(&a, &b));
+    }
+    assert!(convolution_any_mod::<ModInt1000000007>(&[], &[]).is_empty());
+}
+
+#[test]
+fn test_convolution_any_mod_max_modulus() {
+    type Mint = StaticModInt<4294967291>;
+    let a = vec![Mint::new(4294967290); 200];
+    let b = vec![Mint::new(4294967290); 300];
+    assert_eq!(convolution_any_mod(&a, &b), naive_convolution(&a, &b));
+}
+
+#[test]
+fn test_convolution_any_mod_dynamic() {
+    modint_id!(ConvolutionId);
+    type Mint = DynamicModInt<ConvolutionId>;
+    Mint::set_modulus(1_000_000_009);
+
+    let mut state = 521288629;
+    let a: Vec<Mint> = (0..150).map(|_| Mint::from(xorshift(&mut state))).collect();
+    let b: Vec<Mint> = (0..250).map(|_| Mint::from(xorshift(&mut state))).collect();
+    assert_eq!(convolution_any_mod(&a, &b), naive_convolution(&a, &b));
+}
+
+type Fps = FormalPowerSeries<998244353>;
+
+fn random_fps(state: &mut u64, n: usize) -> Fps {
+    Fps::new(
+        (0..n)
+            .map(|_| ModInt998244353::from(xorshift(state)))
+            .collect(),
+    )
+}
+
+#[test]
+fn test_fps_arithmetic() {
+    let f = Fps::from_values(&[1, 2, 3]);
+    let g = Fps::from_values(&[4, 5]);
+    assert_eq!(&f + &g, Fps::from_values(&[5, 7, 3]));
+    assert_eq!(&f - &g, Fps::from_values(&[-3, -3, 3]));
+    assert_eq!(f.clone() * g.clone(), Fps::from_values(&[4, 13, 22, 15]));
+    assert_eq!(-g.clone(), Fps::from_values(&[-4, -5]));
+    assert_eq!(f.eval(ModInt998244353::new(2)).value(), 17);
+}
+
+#[test]
+fn test_fps_derivative_integral() {
+    let f = Fps::from_values(&[1, 2, 3, 4]);
+    assert_eq!(f.derivative(), Fps::from_values(&[2, 6, 12]));
+    assert_eq!(f.derivative().integral(), Fps::from_values(&[0, 2, 3, 4]));
+    assert_eq!(Fps::default().integral(), Fps::from_values(&[0]));
+}
+
+#[test]
+fn test_fps_inv() {
+    let f = Fps::from_values(&[1, -1]);
+    assert_eq!(f.inv(5), Fps::from_values(&[1, 1, 1, 1, 1]));
+
+    let mut state = 7;
+    let mut f = random_fps(&mut state, 300);
+    f = &f + &Fps::from_values(&[1]);
+    let g = f.inv(257);
+    let mut one = vec![ModInt998244353::new(0); 257];
+    one[0] = ModInt998244353::new(1);
+    assert_eq!((&f * &g).truncated(257), Fps::new(one));
+}
+
+#[test]
+#[should_panic(expected = "Constant term must be invertible")]
+fn test_fps_inv_zero_constant() {
+    let _ = Fps::from_values(&[0, 1]).inv(4);
+}
+
+#[test]
+fn test_fps_log_exp() {
+    let exp_x = Fps::from_values(&[0, 1]).exp(6);
+    let factorials = [1u64, 1, 2, 6, 24, 120];
+    for (i, &fact) in factorials.iter().enumerate() {
+        assert_eq!(
+            exp_x.coeff(i) * ModInt998244353::from(fact),
+            ModInt998244353::new(1)
+        );
+    }
+
+    let log = Fps::from_values(&[1, -1]).log(5);
+    for i in 1..5 {
+        assert_eq!(
+            log.coeff(i) * ModInt998244353::from(i),
+            ModInt998244353::new(-1)
+        );
+    }
+
+    let mut state = 11;
+    let mut f = random_fps(&mut state, 200);
+    f = &f.truncated(200) - &Fps::new(vec![f.coeff(0)]);
+    let g = f.exp(200);
+    assert_eq!(g.log(200), f);
+}
+
+#[test]
+fn test_fps_pow() {
+    let f = Fps::from_values(&[0, 0, 2, 1]);
+    let mut expected = Fps::from_values(&[1]);
+    for _ in 0..3 {
+        expected = (&expected * &f).truncated(10);
+    }
+    assert_eq!(f.pow(3, 10), expected);
+    assert_eq!(f.pow(0, 3), Fps::from_values(&[1, 0, 0]));
+    assert_eq!(f.pow(5, 10), Fps::from_values(&[0; 10]));
+    assert_eq!(f.pow(1 << 40, 10), Fps::from_values(&[0; 10]));
+    assert_eq!(
+        Fps::from_values(&[0, 0]).pow(2, 3),
+        Fps::from_values(&[0; 3])
+    );
+
+    let mut state = 13;
+    let g = random_fps(&mut state, 100);
+    let mut expected = Fps::from_values(&[1]);
+    for _ in 0..7 {
+        expected = (&expected * &g).truncated(100);
+    }
+    assert_eq!(g.pow(7, 100), expected);
+}
+
+#[test]
+fn test_fps_sqrt() {
+    let f = Fps::from_values(&[0, 0, 4, 4, 1]);
+    let root = f.sqrt(5).unwrap();
+    assert_eq!((&root * &root).truncated(5), f);
+    assert_eq!(Fps::from_values(&[0, 1]).sqrt(3), None);
+    assert_eq!(Fps::from_values(&[3]).sqrt(3), None);
+    assert_eq!(
+        Fps::from_values(&[0, 0]).sqrt(2),
+        Some(Fps::from_values(&[0, 0]))
+    );
+
+    let mut state = 17;
+    let g = random_fps(&mut state, 150);
+    let square = (&g * &g).truncated(150);
+    let root = square.sqrt(150).unwrap();
+    assert_eq!((&root * &root).truncated(150), square);
+}
+
+#[test]
+fn test_fps_div_rem() {
+    let f = Fps::from_values(&[-1, 0, 0, 1]);
+    let g = Fps::from_values(&[-1, 1]);
+    let (q, r) = f.div_rem(&g);
+    assert_eq!(q, Fps::from_values(&[1, 1, 1]));
+    assert!(r.is_empty());
+
+    let (q, r) = g.div_rem(&f);
+    assert!(q.is_empty());
+    assert_eq!(r, g);
+
+    let mut state = 19;
+    let f = random_fps(&mut state, 300);
+    let g = random_fps(&mut state, 120);
+    let (q, r) = f.div_rem(&g);
+    assert!(r.len() < g.len());
+    assert_eq!((&(&g * &q) + &r).truncated(300), f);
+}
+
+#[test]
+fn test_multipoint_eval() {
+    let f = Fps::from_values(&[1, 2, 3]);
+    let xs: Vec<ModInt998244353> = (0..5).map(ModInt998244353::new).collect();
+    let ys: Vec<u32> = multipoint_eval(&f, &xs).iter().map(|y| y.value()).collect();
+    assert_eq!(ys, vec![1, 6, 17, 34, 57]);
+    assert!(multipoint_eval(&f, &[]).is_empty());
+
+    let mut state = 23;
+    let f = random_fps(&mut state, 100);
+    let xs = random_fps(&mut state, 77).into_vec();
+    let expected: Vec<ModInt998244353> = xs.iter().map(|&x| f.eval(x)).collect();
+    assert_eq!(multipoint_eval(&f, &xs), expected);
+}
+
+#[test]
+fn test_interpolate() {
+    let xs: Vec<ModInt998244353> = (1..=3).map(ModInt998244353::new).collect();
+    let ys: Vec<ModInt998244353> = [6, 17, 34]
+        .iter()
+        .map(|&y| ModInt998244353::new(y))
+        .collect();
+    assert_eq!(interpolate(&xs, &ys), Fps::from_values(&[1, 2, 3]));
+
+    let mut state = 29;
+    let f = random_fps(&mut state, 90);
+    let xs: Vec<ModInt998244353> = (0..90).map(|i| ModInt998244353::new(i * i + 7)).collect();
+    let ys: Vec<ModInt998244353> = xs.iter().map(|&x| f.eval(x)).collect();
+    assert_eq!(interpolate(&xs, &ys), f);
+}
+
+#[test]
+fn test_multipoint_eval_and_interpolate_large() {
+    let mut state = 31;
+    let f = random_fps(&mut state, 1000);
+    let xs = random_fps(&mut state, 1000).into_vec();
+    let ys = multipoint_eval(&f, &xs);
+    let expected: Vec<ModInt998244353> = xs.iter().map(|&x| f.eval(x)).collect();
+    assert_eq!(ys, expected);
+    assert_eq!(interpolate(&xs, &ys), f);
+
+    // More coefficients than points.
+    let g = random_fps(&mut state, 1500);
+    let expected: Vec<ModInt998244353> = xs.iter().map(|&x| g.eval(x)).collect();
+    assert_eq!(multipoint_eval(&g, &xs), expected);
+}
+
+#[test]
+fn test_interpolate_consecutive() {
+    // Sum of squares 0^2 + ... + x^2 is a cubic.
+    let ys: Vec<ModInt1000000007> = [0, 1, 5, 14]
+        .iter()
+        .map(|&y| ModInt1000000007::new(y))
+        .collect();
+    assert_eq!(interpolate_consecutive(&ys, 2).value(), 5);
+    assert_eq!(interpolate_consecutive(&ys, 100).value(), 338350);
+
+    let n = 1_000_000_000_000u64;
+    let expected = ModInt1000000007::from(n)
+        * ModInt1000000007::from(n + 1)
+        * ModInt1000000007::from(2 * n + 1)
+        / ModInt1000000007::new(6);
+    assert_eq!(interpolate_consecutive(&ys, n), expected);
+    assert_eq!(
+        interpolate_consecutive::<ModInt1000000007>(&[], 5).value(),
+        0
+    );
+}
+
+#[test]
+fn test_berlekamp_massey() {
+    let fib: Vec<ModInt998244353> = [1, 1, 2, 3, 5, 8, 13, 21]
+        .iter()
+        .map(|&x| ModInt998244353::new(x))
+        .collect();
+    let coeffs: Vec<u32> = berlekamp_massey(&fib).iter().map(|c| c.value()).collect();
+    assert_eq!(coeffs, vec![1, 1]);
+
+    let zeros = vec![ModInt998244353::new(0); 5];
+    assert!(berlekamp_massey(&zeros).is_empty());
+    assert!(berlekamp_massey::<ModInt998244353>(&[]).is_empty());
+
+    let geometric: Vec<ModInt998244353> = (0..6).map(|i| ModInt998244353::new(3).pow(i)).collect();
+    assert_eq!(berlekamp_massey(&geometric), vec![ModInt998244353::new(3)]);
+}
+
+#[test]
+fn test_berlekamp_massey_random_recurrence() {
+    let mut state = 31;
+    let d = 12;
+    let coeffs: Vec<ModInt1000000007> = (0..d)
+        .map(|_| ModInt1000000007::from(xorshift(&mut state)))
+        .collect();
+    let mut sequence: Vec<ModInt1000000007> = (0..d)
+        .map(|_| ModInt1000000007::from(xorshift(&mut state)))
+        .collect();
+    for i in d..3 * d {
+        let next = (0..d).map(|j| coeffs[j] * sequence[i - 1 - j]).sum();
+        sequence.push(next);
+    }
+    assert_eq!(berlekamp_massey(&sequence), coeffs);
+}
+
+#[test]
+fn test_kth_term_of_linear_recurrence() {
+    let initial = [ModInt1000000007::new(0), ModInt1000000007::new(1)];
+    let coeffs = [ModInt1000000007::new(1), ModInt1000000007::new(1)];
+    assert_eq!(
+        kth_term_of_linear_recurrence(&initial, &coeffs, 1).value(),
+        1
+    );
+    assert_eq!(
+        kth_term_of_linear_recurrence(&initial, &coeffs, 10).value(),
+        55
+    );
+    assert_eq!(
+        kth_term_of_linear_recurrence(&initial, &coeffs, 90).value(),
+        (2880067194370816120u64 % 1000000007) as u32
+    );
+    assert_eq!(
+        kth_term_of_linear_recurrence(&initial, &coeffs, 1_000_000_000_000_000_000).value(),
+        209783453
+    );
+    assert_eq!(kth_term_of_linear_recurrence(&initial, &[], 5).value(), 0);
+}
+
+#[test]
+fn test_bostan_mori_matches_brute_force() {
+    let mut state = 37;
+    let coeffs = random_fps(&mut state, 8).into_vec();
+    let mut sequence = random_fps(&mut state, 8).into_vec();
+    for i in 8..300 {
+        let next = (0..8).map(|j| coeffs[j] * sequence[i - 1 - j]).sum();
+        sequence.push(next);
+    }
+    for k in [0u64, 7, 8, 100, 299] {
+        assert_eq!(
+            kth_term_of_linear_recurrence(&sequence[..8], &coeffs, k),
+            sequence[k as usize]
+        );
+    }
+
+    // 1 / (1 - x)^2 = 1 + 2x + 3x^2 + ...
+    let q = [
+        ModInt998244353::new(1),
+        ModInt998244353::new(-2),
+        ModInt998244353::new(1),
+    ];
+    assert_eq!(
+        bostan_mori(&[ModInt998244353::new(1)], &q, 1_000_000).value(),
+        1_000_001
+    );
+
+    // Numerator longer than the denominator: (1 + x)^5 / (1 - x).
+    let p = Fps::from_values(&[1, 5, 10, 10, 5, 1]).into_vec();
+    let q = [ModInt998244353::new(1), ModInt998244353::new(-1)];
+    for (k, expected) in [(0, 1), (2, 16), (5, 32), (1000, 32)] {
+        assert_eq!(bostan_mori(&p, &q, k).value(), expected);
+    }
+}
+
+#[test]
+fn test_bostan_mori_non_ntt_modulus() {
+    type Mint = ModInt1000000007;
+    let mut state = 41;
+    let mut next = || Mint::from(xorshift(&mut state));
+    let coeffs: Vec<Mint> = (0..70).map(|_| next()).collect();
+    let mut sequence: Vec<Mint> = (0..70).map(|_| next()).collect();
+    for i in 70..400 {
+        let next = (0..70).map(|j| coeffs[j] * sequence[i - 1 - j]).sum();
+        sequence.push(next);
+    }
+    for k in [69u64, 70, 255, 399] {
+        assert_eq!(
+            kth_term_of_linear_recurrence(&sequence[..70], &coeffs, k),
+            sequence[k as usize]
+        );
+    }
+}
+
+#[test]
+fn test_kth_term_of_linear_recurrence_large_order() {
+    // a_i = 2 a_{i - d}, so a_k = 2^(k / d) a_{k mod d}.
+    let d = 4_093;
+    let k = 1_000_000_000_000_000_000u64;
+    let initial: Vec<ModInt998244353> = (0..d).map(|i| ModInt998244353::new(i * 7 + 1)).collect();
+    let mut coeffs = vec![ModInt998244353::new(0); d as usize];
+    coeffs[d as usize - 1] = ModInt998244353::new(2);
+
+    let result = kth_term_of_linear_recurrence(&initial, &coeffs, k);
+    let expected = ModInt998244353::new(2).pow(k / d as u64) * initial[(k % d as u64) as usize];
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn test_combination_binom() {
+    let comb = Combination::<ModInt1000000007>::new(100);
+    assert_eq!(comb.binom(5, 2).value(), 10);
+    assert_eq!(comb.binom(10, 0).value(), 1);
+    assert_eq!(comb.binom(10, 10).value(), 1);
+    assert_eq!(comb.binom(100, 50).value(), 538992043);
+    assert_eq!(comb.binom(3, 5).value(), 0);
+    assert_eq!(comb.binom(-1, 0).value(), 0);
+    assert_eq!(comb.binom(5, -1).value(), 0);
+    assert_eq!(comb.fact(5).value(), 120);
+    assert_eq!((comb.fact(7) * comb.inv_fact(7)).value(), 1);
+    assert_eq!(
+        comb.inv(4) * ModInt1000000007::new(4),
+        ModInt1000000007::new(1)
+    );
+}
+
+#[test]
+fn test_combination_perm_multinom_homogeneous() {
+    let comb = Combination::<ModInt998244353>::new(50);
+    assert_eq!(comb.perm(5, 2).value(), 20);
+    assert_eq!(comb.perm(5, 6).value(), 0);
+    assert_eq!(comb.multinom(&[2, 1, 1]).value(), 12);
+    assert_eq!(comb.multinom(&[]).value(), 1);
+    assert_eq!(comb.homogeneous(3, 2).value(), 6);
+    assert_eq!(comb.homogeneous(0, 0).value(), 1);
+    assert_eq!(comb.homogeneous(0, 3).value(), 0);
+    assert_eq!(comb.homogeneous(-1, 2).value(), 0);
+}
+
+#[test]
+fn test_combination_catalan_stirling() {
+    let comb = Combination::<ModInt998244353>::new(40);
+    let catalan: Vec<u32> = (0..8).map(|n| comb.catalan(n).value()).collect();
+    assert_eq!(catalan, vec![1, 1, 2, 5, 14, 42, 132, 429]);
+
+    let row: Vec<u32> = comb.stirling2_row(5).iter().map(|x| x.value()).collect();
+    assert_eq!(row, vec![0, 1, 15, 25, 10, 1]);
+    assert_eq!(comb.stirling2_row(0)[0].value(), 1);
+
+    let row = comb.stirling2_row(40);
+    let bell: ModInt998244353 = row.iter().sum();
+    assert_eq!(
+        bell,
+        ModInt998244353::from((157450588391204931289324344702531067u128 % 998244353) as u64)
+    );
+}
+
+#[test]
+fn test_combination_invalid_past_table() {
+    let comb = Combination::<ModInt998244353>::new(10);
+    let zero = ModInt998244353::new(0);
+    assert_eq!(comb.binom(5, 11), zero);
+    assert_eq!(comb.binom(-20, 3), zero);
+    assert_eq!(comb.perm(3, 20), zero);
+    assert_eq!(comb.perm(-1, 20), zero);
+    assert_eq!(comb.homogeneous(0, 20), zero);
+}
+
+fn pascal_triangle(n: usize, m: u64) -> Vec<Vec<u64>> {
+    let mut table = vec![vec![0u64; n + 1]; n + 1];
+    for i in 0..=n {
+        table[i][0] = 1 % m;
+        for j in 1..=i {
+            table[i][j] = (table[i - 1][j - 1] + table[i - 1][j]) % m;
+        }
+    }
+    table
+}
+
+#[test]
+fn test_binom_mod_prime() {
+    let table = pascal_triangle(60, 7);
+    for n in 0..=60u64 {
+        for k in 0..=n + 1 {
+            let expected = if k > n {
+                0
+            } else {
+                table[n as usize][k as usize]
+            };
+            assert_eq!(binom_mod_prime(n, k, 7), expected);
+        }
+    }
+    assert_eq!(
+        binom_mod_prime(1_000_000_000_000_000_000, 500_000_000_000_000_000, 2),
+        0
+    );
+    assert_eq!(
+        binom_mod_prime(756_247_381_085_762_037, 72_757_217_426_062_276, 1_000_003),
+        744_771
+    );
+    assert_eq!(
+        binom_mod_prime(1_000_000_000_000_000_000, 999, 1_000_003),
+        138_335
+    );
+    assert_eq!(
+        binom_mod_prime(1_000_000_000_000, 1, 1_000_003),
+        1_000_000_000_000 % 1_000_003
+    );
+}
+
+#[test]
+fn test_lucas_combination() {
+    let lucas = LucasCombination::new(7);
+    assert_eq!(lucas.modulus(), 7);
+    let table = pascal_triangle(60, 7);
+    for n in 0..=60u64 {
+        for k in 0..=n {
+            assert_eq!(lucas.binom(n, k), table[n as usize][k as usize]);
+        }
+        assert_eq!(lucas.binom(n, n + 1), 0);
+    }
+
+    let lucas = LucasCombination::new(1_000_003);
+    assert_eq!(
+        lucas.binom(756_247_381_085_762_037, 72_757_217_426_062_276),
+        744_771
+    );
+    assert_eq!(lucas.binom(1_000_000_000_000_000_000, 999), 138_335);
+}
+
+#[test]
+fn test_binom_mod_large_prime() {
+    let p = 1_000_000_007;
+    let comb = Combination::<ModInt1000000007>::new(1000);
+    for &(n, k) in &[(10u64, 3u64), (1000, 500), (1000, 999), (1000, 0)] {
+        let expected = comb.binom(n as i64, k as i64).value() as u64;
+        assert_eq!(binom_mod_prime(n, k, p), expected);
+        assert_eq!(binom_mod_prime_power(n, k, p, 1), expected);
+    }
+    assert_eq!(binom_mod_any(10, 3, p), 120);
+    assert_eq!(binom_mod_any(10, 3, 2 * p), 120);
+    // C(p - 1, 2) = (-1)(-2) / 2 and C(p + 5, 2) = C(1, 0) * C(5, 2) by Lucas.
+    assert_eq!(binom_mod_prime(p - 1, 2, p), 1);
+    assert_eq!(binom_mod_prime(p + 5, 2, p), 10);
+    assert_eq!(binom_mod_prime(p + 5, p + 6, p), 0);
+    assert_eq!(binom_mod_prime(2 * p + 1, p + 2, p), 0);
+}
+
+#[test]
+fn test_binom_mod_large_prime_power() {
+    assert_eq!(binom_mod_prime(10, 3, 16_777_213), 120);
+    assert_eq!(binom_mod_prime_power(10, 3, 5, 11), 120);
+    for &m in &[1u64 << 24, 1 << 25, 1 << 30] {
+        assert_eq!(binom_mod_any(10, 3, m), 120);
+    }
+    assert_eq!(
+        binom_mod_prime_power(1_000_000_000_000_000_007, 60, 2, 40),
+        402_412_961_792
+    );
+    assert_eq!(
+        binom_mod_prime_power(1_073_754_169, 1000, 5, 13),
+        1_130_583_346
+    );
+    assert_eq!(
+        binom_mod_prime_power(1_000_000_000_000_000_001, 50, 3, 4),
+        27
+    );
+
+    // C(p - 1, k) = (-1)^k, with a Lucas digit wider than any table.
+    let p = 1_000_000_007;
+    assert_eq!(binom_mod_prime(p - 1, 16_777_219, p), p - 1);
+}
+
+#[test]
+fn test_binom_mod_prime_power() {
+    for &(p, e) in &[(2u64, 1u32), (2, 3), (2, 6), (3, 4), (5, 2), (7, 1)] {
+        let m = p.pow(e);
+        let table = pascal_triangle(80, m);
+        for n in 0..=80u64 {
+            for k in 0..=n {
+                assert_eq!(
+                    binom_mod_prime_power(n, k, p, e),
+                    table[n as usize][k as usize],
+                    "C({}, {}) mod {}^{}",
+                    n,
+                    k,
+                    p,
+                    e
+                );
+            }
+        }
+    }
+    assert_eq!(binom_mod_prime_power(5, 7, 2, 3), 0);
+}
+
+#[test]
+fn test_binom_mod_any() {
+    for &m in &[1u64, 6, 12, 100, 360, 1001] {
+        let table = pascal_triangle(60, m);
+        for n in 0..=60u64 {
+            for k in 0..=n {
+                assert_eq!(binom_mod_any(n, k, m), table[n as usize][k as usize]);
+            }
+        }
+    }
+    // C(10^18, 10^9) mod 10^6 via p-adic parts 2^6 and 5^6.
+    let m = 1_000_000;
+    let result = binom_mod_any(1_000_000_000_000_000_000, 1_000_000_000, m);
+    assert_eq!(
+        result % 64,
+        binom_mod_prime_power(1_000_000_000_000_000_000, 1_000_000_000, 2, 6)
+    );
+    assert_eq!(
+        result % 15625,
+        binom_mod_prime_power(1_000_000_000_000_000_000, 1_000_000_000, 5, 6)
+    );
+}
+
+fn mint_matrix(values: &[&[i64]]) -> Matrix<ModInt998244353> {
+    Matrix::from_vec(
+        values
+            .iter()
+            .map(|row| row.iter().map(|&x| ModInt998244353::new(x)).collect())
+            .collect(),
+    )
+}
+
+#[test]
+fn test_matrix_mul_pow() {
+    let fib = Matrix::from_vec(vec![vec![1u64, 1], vec![1, 0]]);
+    assert_eq!(fib.pow(10)[(0, 1)], 55);
+    assert_eq!(fib.pow(0), Matrix::identity(2));
+
+    let a = Matrix::from_vec(vec![vec![1i64, 2, 3], vec![4, 5, 6]]);
+    let b = a.transpose();
+    assert_eq!(&a * &b, Matrix::from_vec(vec![vec![14, 32], vec![32, 77]]));
+    assert_eq!((a.rows(), a.cols()), (2, 3));
+
+    let m = Matrix::from_vec(vec![
+        vec![ModInt1000000007::new(1), ModInt1000000007::new(1)],
+        vec![ModInt1000000007::new(1), ModInt1000000007::new(0)],
+    ]);
+    assert_eq!(m.pow(1_000_000_000_000_000_000)[(0, 1)].value(), 209783453);
+}
+
+#[test]
+fn test_matrix_min_plus() {
+    let inf = MinPlus(i64::MAX);
+    let graph = Matrix::from_vec(vec![
+        vec![MinPlus(0), MinPlus(4), MinPlus(1), inf],
+        vec![inf, MinPlus(0), inf, MinPlus(1)],
+        vec![inf, MinPlus(2), MinPlus(0), MinPlus(6)],
+        vec![inf, inf, inf, MinPlus(0)],
+    ]);
+    let dist = graph.pow(3);
+    assert_eq!(dist[(0, 1)], MinPlus(3));
+    assert_eq!(dist[(0, 3)], MinPlus(4));
+    assert_eq!(dist[(3, 0)], inf);
+
+    // Walks of exactly two edges.
+    let edges = Matrix::from_vec(vec![
+        vec![MinPlus(i64::MAX), MinPlus(5)],
+        vec![MinPlus(1), MinPlus(i64::MAX)],
+    ]);
+    assert_eq!(edges.pow(2)[(0, 0)], MinPlus(6));
+}
+
+#[test]
+fn test_matrix_max_plus() {
+    let ninf = MaxPlus(i64::MIN);
+    let graph = Matrix::from_vec(vec![
+        vec![ninf, MaxPlus(3), MaxPlus(1)],
+        vec![ninf, ninf, MaxPlus(2)],
+        vec![MaxPlus(1), ninf, ninf],
+    ]);
+    assert_eq!(graph.pow(3)[(0, 0)], MaxPlus(6));
+    assert_eq!(graph.pow(2)[(0, 2)], MaxPlus(5));
+}
+
+#[test]
+fn test_matrix_determinant_rank() {
+    let a = mint_matrix(&[&[2, 0, 1], &[1, 3, 2], &[1, 1, 2]]);
+    assert_eq!(a.determinant(), ModInt998244353::new(6));
+    assert_eq!(a.rank(), 3);
+
+    let b = mint_matrix(&[&[1, 2, 3], &[2, 4, 6], &[1, 1, 1]]);
+    assert_eq!(b.determinant(), ModInt998244353::new(0));
+    assert_eq!(b.rank(), 2);
+
+    let c = mint_matrix(&[&[0, 1], &[1, 0]]);
+    assert_eq!(c.determinant(), ModInt998244353::new(-1));
+    assert_eq!(mint_matrix(&[&[1, 2, 3, 4]]).rank(), 1);
+}
+
+#[test]
+fn test_matrix_inverse() {
+    let a = mint_matrix(&[&[2, 0, 1], &[1, 3, 2], &[1, 1, 2]]);
+    let inv = a.inverse().unwrap();
+    assert_eq!(&a * &inv, Matrix::identity(3));
+    assert_eq!(&inv * &a, Matrix::identity(3));
+    assert!(mint_matrix(&[&[1, 2], &[2, 4]]).inverse().is_none());
+
+    let empty = Matrix::<ModInt998244353>::new(0, 0);
+    assert_eq!(empty.determinant(), ModInt998244353::new(1));
+    assert_eq!(empty.inverse(), Some(Matrix::new(0, 0)));
+}
+
+#[test]
+fn test_matrix_solve() {
+    let a = mint_matrix(&[&[1, 1, 1], &[0, 2, 5], &[2, 5, -1]]);
+    let b: Vec<ModInt998244353> = [6, -4, 27]
+        .iter()
+        .map(|&x| ModInt998244353::new(x))
+        .collect();
+    let x = a.solve(&b).unwrap();
+    assert_eq!(
+        x,
+        vec![
+            ModInt998244353::new(5),
+            ModInt998244353::new(3),
+            ModInt998244353::new(-2)
+        ]
+    );
+
+    let singular = mint_matrix(&[&[1, 2], &[2, 4]]);
+    let consistent = [ModInt998244353::new(3), ModInt998244353::new(6)];
+    let x = singular.solve(&consistent).unwrap();
+    assert_eq!(
+        x[0] + x[1] * ModInt998244353::new(2),
+        ModInt998244353::new(3)
+    );
+    let inconsistent = [ModInt998244353::new(3), ModInt998244353::new(7)];
+    assert!(singular.solve(&inconsistent).is_none());
+}
+
+fn bits(s: &str) -> Vec<bool> {
+    s.chars().map(|c| c == '1').collect()
+}
+
+#[test]
+fn test_bit_matrix_get_set() {
+    let mut m = BitMatrix::new(2, 130);
+    assert!(!m.get(1, 129));
+    m.set(1, 129, true);
+    m.set(0, 64, true);
+    assert!(m.get(1, 129));
+    assert!(m.get(0, 64));
+    m.set(1, 129, false);
+    assert!(!m.get(1, 129));
+    assert_eq!((m.rows(), m.cols()), (2, 130));
+}
+
+#[test]
+fn test_bit_matrix_rank_solve() {
+    let a = BitMatrix::from_vec(&[bits("110"), bits("011"), bits("101")]);
+    assert_eq!(a.rank(), 2);
+
+    let x = a.solve(&bits("101")).unwrap();
+    for (i, row) in [bits("110"), bits("011"), bits("101")].iter().enumerate() {
+        let dot = row.iter().zip(&x).filter(|&(&r, &v)| r && v).count() % 2 == 1;
+        assert_eq!(dot, [true, false, true][i]);
+    }
+    assert!(a.solve(&bits("100")).is_none());
+
+    let identity = BitMatrix::from_vec(&[bits("100"), bits("010"), bits("001")]);
+    assert_eq!(identity.solve(&bits("011")), Some(bits("011")));
+}
+
+#[test]
+fn test_bit_matrix_nullspace() {
+    let rows = [bits("1100"), bits("0110")];
+    let a = BitMatrix::from_vec(&rows);
+    let kernel = a.nullspace();
+    assert_eq!(kernel.len(), 2);
+    for x in &kernel {
+        for row in &rows {
+            let dot = row.iter().zip(x).filter(|&(&r, &b)| r && b).count();
+            assert_eq!(dot % 2, 0);
+        }
+    }
+    assert!(BitMatrix::from_vec(&[bits("10"), bits("01")])
+        .nullspace()
+        .is_empty());
+}
+
+#[test]
+fn test_bit_matrix_wide() {
+    let mut state = 41;
+    let (n, m) = (70, 150);
+    let mut a = BitMatrix::new(n, m);
+    for i in 0..n {
+        for j in 0..m {
+            a.set(i, j, xorshift(&mut state) & 1 == 1);
+        }
+    }
+    let x_true: Vec<bool> = (0..m).map(|_| xorshift(&mut state) & 1 == 1).collect();
+    let b: Vec<bool> = (0..n)
+        .map(|i| (0..m).filter(|&j| a.get(i, j) && x_true[j]).count() % 2 == 1)
+        .collect();
+
+    let x = a.solve(&b).unwrap();
+    for (i, &bit) in b.iter().enumerate() {
+        assert_eq!(
+            (0..m).filter(|&j| a.get(i, j) && x[j]).count() % 2 == 1,
+            bit
+        );
+    }
+    assert_eq!(a.nullspace().len(), m - a.rank());
+}
+
+#[test]
+fn test_xor_basis() {
+    let mut basis = XorBasis::new();
+    assert!(basis.insert(0b1010));
+    assert!(basis.insert(0b0110));
+    assert!(!basis.insert(0b1100));
+    assert!(basis.insert(0b0001));
+    assert_eq!(basis.rank(), 3);
+    assert!(basis.contains(0b1101));
+    assert!(!basis.contains(0b10000));
+    assert_eq!(basis.max_xor(), 0b1101);
+
+    let mut all: Vec<u64> = (0..8u64)
+        .map(|mask| {
+            [0b1010u64, 0b0110, 0b0001]
+                .iter()
+                .enumerate()
+                .filter(|&(i, _)| mask >> i & 1 == 1)
+                .fold(0, |acc, (_, &v)| acc ^ v)
+        })
+        .collect();
+    all.sort_unstable();
+    for (k, &value) in all.iter().enumerate() {
+        assert_eq!(basis.kth_smallest(k as u64), Some(value));
+    }
+    assert_eq!(basis.kth_smallest(8), None);
+    assert_eq!(XorBasis::new().kth_smallest(0), Some(0));
+}
+
+#[test]
+fn test_xor_basis_full_rank() {
+    let mut basis = XorBasis::new();
+    for bit in 0..64 {
+        basis.insert(1 << bit);
+    }
+    assert!(!basis.insert(u64::MAX));
+    assert_eq!(basis.max_xor(), u64::MAX);
+    assert_eq!(basis.kth_smallest(u64::MAX), Some(u64::MAX));
+    assert_eq!(basis.kth_smallest(12345), Some(12345));
+}
+
+#[test]
+fn test_discrete_log() {
+    assert_eq!(discrete_log(2, 1, 7), Some(0));
+    assert_eq!(discrete_log(3, 13, 17), Some(4));
+    assert_eq!(discrete_log(2, 3, 7), None);
+    assert_eq!(discrete_log(5, 0, 1), Some(0));
+    assert_eq!(discrete_log(2, 0, 8), Some(3));
+    assert_eq!(discrete_log(6, 4, 10), None);
+    assert_eq!(discrete_log(6, 6, 10), Some(1));
+
+    let p = 1_000_000_007;
+    let x = discrete_log(5, 123_456_789, p).unwrap();
+    assert_eq!(pow_mod(5, x, p), 123_456_789);
+}
+
+#[test]
+fn test_discrete_log_matches_brute_force() {
+    for m in 1..=60u64 {
+        for a in 0..m {
+            for b in 0..m {
+                let mut value = 1 % m;
+                let mut expected = None;
+                for x in 0..=2 * m {
+                    if value == b {
+                        expected = Some(x);
+                        break;
+                    }
+                    value = value * a % m;
+                }
+                assert_eq!(discrete_log(a, b, m), expected, "{}^x = {} mod {}", a, b, m);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_sqrt_mod() {
+    assert_eq!(sqrt_mod(0, 7), Some(0));
+    assert_eq!(sqrt_mod(2, 7), Some(3));
+    assert_eq!(sqrt_mod(3, 7), None);
+    assert_eq!(sqrt_mod(1, 2), Some(1));
+    for p in [998_244_353u64, 1_000_000_007, (1 << 61) - 1] {
+        let root = sqrt_mod(5, p).unwrap_or(0);
+        if root != 0 {
+            assert_eq!(mul_mod(root, root, p), 5);
+        }
+        let r = sqrt_mod(123_456_789 * 123_456_789 % p, p).unwrap();
+        assert!(r == 123_456_789 || r == p - 123_456_789);
+    }
+    for p in [3u64, 5, 13, 17, 97] {
+        let squares: Vec<u64> = (0..p).map(|x| x * x % p).collect();
+        for a in 0..p {
+            match sqrt_mod(a, p) {
+                Some(r) => assert_eq!(r * r % p, a),
+                None => assert!(!squares.contains(&a)),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_kth_root_mod() {
+    for p in [2u64, 7, 13, 101] {
+        for k in 0..12u64 {
+            let powers: Vec<u64> = (0..p).map(|x| pow_mod(x, k, p)).collect();
+            for a in 0..p {
+                match kth_root_mod(a, k, p) {
+                    Some(x) => assert_eq!(pow_mod(x, k, p), a),
+                    None => assert!(!powers.contains(&a), "{}-th root of {} mod {}", k, a, p),
+                }
+            }
+        }
+    }
+
+    let p = 998_244_353;
+    let x = kth_root_mod(pow_mod(12345, 7, p), 7, p).unwrap();
+    assert_eq!(pow_mod(x, 7, p), pow_mod(12345, 7, p));
+}
+
+#[test]
+fn test_floor_sum() {
+    assert_eq!(floor_sum(4, 10, 6, 3), 3);
+    assert_eq!(floor_sum(6, 5, 4, 3), 13);
+    assert_eq!(floor_sum(1, 1, 0, 0), 0);
+    assert_eq!(floor_sum(31415, 92653, 58979, 32384), 314095480);
+    assert_eq!(
+        floor_sum(1000000000, 1000000000, 999999999, 999999999),
+        499999999500000000
+    );
+    assert_eq!(floor_sum(0, 3, 5, 7), 0);
+}
+
+#[test]
+fn test_floor_sum_matches_naive() {
+    for n in 0..15i64 {
+        for m in 1..15i64 {
+            for a in -15..15i64 {
+                for b in -15..15i64 {
+                    let expected: i64 = (0..n).map(|i| (a * i + b).div_euclid(m)).sum();
+                    assert_eq!(floor_sum(n, m, a, b), expected);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct FloorSumNode {
+    x: u64,
+    y: u64,
+    sum: u64,
+}
+
+impl Monoid for FloorSumNode {
+    fn identity() -> Self {
+        FloorSumNode { x: 0, y: 0, sum: 0 }
+    }
+
+    fn combine(&self, other: &Self) -> Self {
+        FloorSumNode {
+            x: self.x + other.x,
+            y: self.y + other.y,
+            sum: self.sum + other.sum + self.y * other.x,
+        }
+    }
+}
+
+#[test]
+fn test_universal_euclid() {
+    let up = FloorSumNode { x: 0, y: 1, sum: 0 };
+    let right = FloorSumNode { x: 1, y: 0, sum: 0 };
+    for p in 0..12u64 {
+        for q in 1..12u64 {
+            for r in 0..30u64 {
+                for l in 0..12u64 {
+                    let node = universal_euclid(p, q, r, l, &up, &right);
+                    let expected: u64 = (1..=l).map(|x| (p * x + r) / q).sum();
+                    assert_eq!(node.sum, expected);
+                    assert_eq!(node.x, l);
+                    assert_eq!(node.y, (p * l + r) / q);
+                }
+            }
+        }
+    }
+
+    let node = universal_euclid(58979, 92653, 32384, 31414, &up, &right);
+    assert_eq!(node.sum, floor_sum(31415, 92653, 58979, 32384) as u64);
+}
+
+#[test]
+fn test_quotient_ranges() {
+    let ranges: Vec<(u64, u64, u64)> = quotient_ranges(10).collect();
+    assert_eq!(
+        ranges,
+        vec![(10, 1, 1), (5, 2, 2), (3, 3, 3), (2, 4, 5), (1, 6, 10)]
+    );
+    assert_eq!(quotient_ranges(0).count(), 0);
+
+    let n = 1_000_000_000_000u64;
+    let mut covered = 0;
+    let mut divisor_sum = 0u128;
+    for (q, l, r) in quotient_ranges(n) {
+        assert_eq!(n / l, q);
+        assert_eq!(n / r, q);
+        assert!(r == n || n / (r + 1) < q);
+        covered += r - l + 1;
+        divisor_sum += q as u128 * (r - l + 1) as u128;
+    }
+    assert_eq!(covered, n);
+    assert_eq!(divisor_sum, 27785452449086);
+}
+
+#[test]
+fn test_quotient_ranges_from() {
+    let ranges: Vec<(u64, u64, u64)> = quotient_ranges_from(10, 4).collect();
+    assert_eq!(ranges, vec![(2, 4, 5), (1, 6, 10)]);
+    assert_eq!(quotient_ranges_from(10, 11).count(), 0);
+
+    let ranges: Vec<(u64, u64, u64)> = quotient_ranges_from(u64::MAX, u64::MAX / 2).collect();
+    assert_eq!(
+        ranges,
+        vec![
+            (2, u64::MAX / 2, u64::MAX / 2),
+            (1, u64::MAX / 2 + 1, u64::MAX)
+        ]
+    );
+    assert_eq!(
+        quotient_ranges_from(u64::MAX, u64::MAX).collect::<Vec<_>>(),
+        vec![(1, u64::MAX, u64::MAX)]
+    );
+    assert_eq!(quotient_ranges(u64::MAX).next(), Some((u64::MAX, 1, 1)));
+}
+
+#[test]
+fn test_phi_and_mobius_prefix_sum_small() {
+    let sieve = Sieve::new(3000);
+    let phi = sieve.phi();
+    let mobius = sieve.mobius();
+    let mut phi_sum = 0u128;
+    let mut mobius_sum = 0i64;
+    for n in 1..=3000 {
+        phi_sum += phi[n] as u128;
+        mobius_sum += mobius[n] as i64;
+        if n % 97 == 0 || n < 50 {
+            assert_eq!(phi_prefix_sum(n as u64), phi_sum);
+            assert_eq!(mobius_prefix_sum(n as u64), mobius_sum);
+        }
+    }
+    assert_eq!(phi_prefix_sum(0), 0);
+    assert_eq!(mobius_prefix_sum(0), 0);
+}
+
+#[test]
+fn test_phi_and_mobius_prefix_sum_large() {
+    assert_eq!(phi_prefix_sum(1_000_000), 303963552392);
+    assert_eq!(mobius_prefix_sum(1_000_000), 212);
+    assert_eq!(phi_prefix_sum(1_000_000_000), 303963551173008414);
+    assert_eq!(mobius_prefix_sum(1_000_000_000), -222);
+}
+
+#[test]
+fn test_min25_sieve_matches_naive() {
+    type Mint = ModInt998244353;
+    let sieve = Sieve::new(2000);
+    let phi = sieve.phi();
+    let mobius = sieve.mobius();
+    let divisor_count: Vec<u64> = (0..=2000u64)
+        .map(|i| if i == 0 { 0 } else { divisors(i).len() as u64 })
+        .collect();
+
+    for n in [1u64, 2, 3, 10, 97, 500, 1024, 2000] {
+        let phi_sum: u64 = (1..=n as usize).map(|i| phi[i] as u64).sum();
+        let got = min25_sieve(n, &[Mint::new(-1), Mint::new(1)], |p, e| {
+            Mint::from(p).pow(e as u64) - Mint::from(p).pow(e as u64 - 1)
+        });
+        assert_eq!(got, Mint::from(phi_sum));
+
+        let mobius_sum: i64 = (1..=n as usize).map(|i| mobius[i] as i64).sum();
+        let got = min25_sieve(n, &[Mint::new(-1)], |_, e| Mint::new(-i64::from(e == 1)));
+        assert_eq!(got, Mint::new(mobius_sum));
+
+        let d_sum: u64 = divisor_count[1..=n as usize].iter().sum();
+        let got = min25_sieve(n, &[Mint::new(2)], |_, e| Mint::from(e as u64 + 1));
+        assert_eq!(got, Mint::from(d_sum));
+    }
+    assert_eq!(
+        min25_sieve(0, &[Mint::new(1)], |_, _| Mint::new(1)),
+        Mint::new(0)
+    );
+}
+
+#[test]
+fn test_min25_sieve_large() {
+    type Mint = ModInt998244353;
+    let n = 1_000_000_000u64;
+    let got = min25_sieve(n, &[Mint::new(-1), Mint::new(1)], |p, e| {
+        Mint::from(p).pow(e as u64) - Mint::from(p).pow(e as u64 - 1)
+    });
+    assert_eq!(got, Mint::from((phi_prefix_sum(n) % 998244353) as u64));
+}
+
+fn random_bigint(state: &mut u64, max_digits: u64) -> BigInt {
+    let len = 1 + xorshift(state) % max_digits;
+    let mut s: String = (0..len)
+        .map(|_| char::from(b'0' + (xorshift(state) % 10) as u8))
+        .collect();
+    if xorshift(state).is_multiple_of(2) {
+        s.insert(0, '-');
+    }
+    s.parse().unwrap()
+}
+
+#[test]
+fn test_bigint_parse_and_display() {
+    let cases = [
+        ("0", "0"),
+        ("-0", "0"),
+        ("+000123", "123"),
+        ("-1000000000", "-1000000000"),
+        ("999999999999999999", "999999999999999999"),
+        ("-12345678901234567890123", "-12345678901234567890123"),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(input.parse::<BigInt>().unwrap().to_string(), expected);
+    }
+    for input in ["", "-", "+", "12a", "1 2", "--1"] {
+        assert!(input.parse::<BigInt>().is_err());
+    }
+    assert_eq!(format!("{:>6}", BigInt::from(-42)), "   -42");
+    assert_eq!(BigInt::from(i64::MIN).to_string(), i64::MIN.to_string());
+    assert_eq!(BigInt::from(u128::MAX).to_string(), u128::MAX.to_string());
+}
+
+#[test]
+fn test_bigint_matches_i128() {
+    let mut state = 0x1234_5678_9abc_def0u64;
+    for _ in 0..2000 {
+        let a = xorshift(&mut state) as i64 as i128 >> (xorshift(&mut state) % 60);
+        let b = xorshift(&mut state) as i64 as i128 >> (xorshift(&mut state) % 60);
+        let (x, y) = (BigInt::from(a), BigInt::from(b));
+
+        assert_eq!(&x + &y, BigInt::from(a + b));
+        assert_eq!(&x - &y, BigInt::from(a - b));
+        assert_eq!(&x * &y, BigInt::from(a * b));
+        assert_eq!(x.cmp(&y), a.cmp(&b));
+        if b != 0 {
+            assert_eq!(&x / &y, BigInt::from(a / b));
+            assert_eq!(&x % &y, BigInt::from(a % b));
+        }
+        assert_eq!(x.to_i64(), Some(a as i64));
+    }
+}
+
+#[test]
+fn test_bigint_large_values() {
+    let factorial: BigInt = (1..=100u32).map(BigInt::from).product();
+    assert_eq!(
+        factorial.to_string(),
+        "93326215443944152681699238856266700490715968264381621468592963895217599993229915608941463976156518286253697920827223758251185210916864000000000000000000000000"
+    );
+    assert_eq!(
+        BigInt::from(3).pow(200).to_string(),
+        "265613988875874769338781322035779626829233452653394495974574961739092490901302182994384699044001"
+    );
+    assert_eq!(
+        BigInt::from(-7).pow(50) / BigInt::from(-1),
+        "-1798465042647412146620280340569649349251249"
+            .parse()
+            .unwrap()
+    );
+    assert_eq!(BigInt::from(5).pow(0), BigInt::one());
+    assert_eq!(factorial.to_i64(), None);
+
+    // (10^n - 1)^2 = 99..9800..01, large enough to go through Karatsuba.
+    let n = 2000;
+    let nines: BigInt = "9".repeat(n).parse().unwrap();
+    let expected = format!("{}8{}1", "9".repeat(n - 1), "0".repeat(n - 1));
+    assert_eq!((&nines * &nines).to_string(), expected);
+}
+
+#[test]
+fn test_bigint_division_identity() {
+    let mut state = 0xdead_beef_cafe_f00du64;
+    for _ in 0..300 {
+        let a = random_bigint(&mut state, 700);
+        let b = random_bigint(&mut state, 400);
+        if b.is_zero() {
+            continue;
+        }
+        let (q, r) = a.div_rem(&b);
+        assert_eq!(&q * &b + &r, a);
+        assert!(r.abs() < b.abs());
+        assert!(r.is_zero() || r.is_negative() == a.is_negative());
+
+        let product = &a * &b;
+        assert_eq!(&product / &b, a);
+        assert!((&product % &b).is_zero());
+        assert_eq!(&product - &a * (&b - BigInt::one()), a);
+    }
+}
+
+#[test]
+#[should_panic(expected = "Division by zero")]
+fn test_bigint_division_by_zero() {
+    let _ = BigInt::from(1) / BigInt::zero();
+}
+
+#[test]
+fn test_rational_normalization_and_display() {
+    let r = Rational::new(6i64, -4);
+    assert_eq!((r.numer(), r.denom()), (-3, 2));
+    assert_eq!(r, Rational::new(-9, 6));
+    assert_eq!(r.to_string(), "-3/2");
+    assert_eq!(Rational::new(0i32, -7), Rational::zero());
+    assert_eq!(Rational::new(0i32, -7).denom(), 1);
+    assert_eq!(Rational::from(5i32).to_string(), "5");
+    assert!(Rational::new(10i64, 5).is_integer());
+    assert_eq!(Rational::checked_new(i32::MIN, -1), None);
+}
+
+#[test]
+fn test_rational_arithmetic() {
+    let a = Rational::new(1i64, 6);
+    let b = Rational::new(-3i64, 4);
+    assert_eq!(a + b, Rational::new(-7, 12));
+    assert_eq!(a - b, Rational::new(11, 12));
+    assert_eq!(a * b, Rational::new(-1, 8));
+    assert_eq!(a / b, Rational::new(-2, 9));
+    assert_eq!(-b, Rational::new(3, 4));
+    assert_eq!(b.abs(), Rational::new(3, 4));
+    assert_eq!(b.recip(), Rational::new(-4, 3));
+    assert_eq!(b.pow(3), Rational::new(-27, 64));
+    assert_eq!(b.pow(-2), Rational::new(16, 9));
+    assert_eq!(a.pow(0), Rational::one());
+
+    let mut c = a;
+    c += b;
+    c *= Rational::from(12);
+    c -= Rational::from(1);
+    c /= Rational::new(2, 3);
+    assert_eq!(c, Rational::from(-12));
+
+    let harmonic: Rational<i64> = (1..=20).map(|k| Rational::new(1, k)).sum();
+    assert_eq!(harmonic, Rational::new(55835135, 15519504));
+    let product: Rational<i64> = (1..=10).map(|k| Rational::new(k, k + 1)).product();
+    assert_eq!(product, Rational::new(1, 11));
+
+    assert_eq!(Rational::new(7i32, 2).floor(), 3);
+    assert_eq!(Rational::new(7i32, 2).ceil(), 4);
+    assert_eq!(Rational::new(-7i32, 2).floor(), -4);
+    assert_eq!(Rational::new(-7i32, 2).ceil(), -3);
+    assert_eq!(Rational::new(-8i32, 2).ceil(), -4);
+    assert!((Rational::new(1i64, 3).to_f64() - 1.0 / 3.0).abs() < 1e-15);
+}
+
+#[test]
+fn test_rational_ordering() {
+    let mut values: Vec<Rational<i32>> = [(1, 2), (-1, 3), (2, 3), (0, 1), (-5, 2), (3, 6)]
+        .iter()
+        .map(|&(n, d)| Rational::new(n, d))
+        .collect();
+    values.sort();
+    let sorted: Vec<String> = values.iter().map(|r| r.to_string()).collect();
+    assert_eq!(sorted, ["-5/2", "-1/3", "0", "1/2", "1/2", "2/3"]);
+
+    let mut state = 0x9e37_79b9_7f4a_7c15u64;
+    for _ in 0..1000 {
+        let mut next = || (xorshift(&mut state) % 2001) as i64 - 1000;
+        let (a, b, c, d) = (next(), next(), next(), next());
+        if b == 0 || d == 0 {
+            continue;
+        }
+        let (x, y) = (Rational::new(a, b), Rational::new(c, d));
+        let expected = (a * d * b.signum() * d.signum()).cmp(&(c * b * b.signum() * d.signum()));
+        assert_eq!(x.cmp(&y), expected);
+    }
+
+    // Cross products overflow i128 here, exercising the fallback.
+    let m = i128::MAX;
+    assert!(Rational::new(m, m - 1) < Rational::new(m - 1, m - 2));
+    assert!(Rational::new(-m, m - 1) > Rational::new(-(m - 1), m - 2));
+    assert_eq!(
+        Rational::new(m - 1, m).cmp(&Rational::new(m - 1, m)),
+        std::cmp::Ordering::Equal
+    );
+}
+
+#[test]
+fn test_rational_checked_overflow() {
+    let max = Rational::from(i32::MAX);
+    assert_eq!(max.checked_add(&Rational::one()), None);
+    assert_eq!(
+        max.checked_sub(&Rational::one()),
+        Some(Rational::from(i32::MAX - 1))
+    );
+    // The sum is in range as a value, but its numerator 2^32 - 3 is not.
+    assert_eq!(max.checked_add(&Rational::new(-1, 2)), None);
+    assert_eq!(Rational::from(i32::MIN).checked_neg(), None);
+    assert_eq!(Rational::from(i32::MIN).checked_abs(), None);
+    assert_eq!(
+        Rational::new(1i64, i64::MAX).checked_mul(&Rational::new(1, 2)),
+        None
+    );
+    assert_eq!(
+        Rational::new(1i64, 3).checked_sub(&Rational::new(1, i64::MAX)),
+        None
+    );
+    assert_eq!(Rational::new(2i64, 3).checked_pow(64), None);
+    assert_eq!(Rational::<i64>::zero().checked_div(&Rational::zero()), None);
+    assert_eq!(Rational::<i64>::zero().checked_recip(), None);
+    assert_eq!(
+        Rational::from(i128::MAX).checked_add(&Rational::one()),
+        None
+    );
+}
+
+#[test]
+#[should_panic(expected = "Rational overflowed")]
+fn test_rational_overflow_panics() {
+    let _ = Rational::from(i64::MAX) * Rational::from(2);
+}
+
+#[test]
+#[should_panic(expected = "Division by zero")]
+fn test_rational_division_by_zero() {
+    let _ = Rational::new(1i32, 2) / Rational::zero();
+}
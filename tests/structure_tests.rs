@@ -5,8 +5,8 @@ fn test_segtree_new() {
     let arr = vec![1, 3, 5, 7, 9];
     let segtree = SegmentTree::new(&arr);
 
-    for i in 0..arr.len() {
-        assert_eq!(segtree.get(i), arr[i]);
+    for (i, &value) in arr.iter().enumerate() {
+        assert_eq!(segtree.get(i), value);
     }
 }
 
@@ -109,8 +109,8 @@ fn test_fenwick_from_vec() {
     let arr = vec![1, 3, 5, 7, 9];
     let ft = FenwickTree::from_vec(&arr);
 
-    for i in 0..arr.len() {
-        assert_eq!(ft.get(i), arr[i]);
+    for (i, &value) in arr.iter().enumerate() {
+        assert_eq!(ft.get(i), value);
     }
 }
 